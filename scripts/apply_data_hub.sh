@@ -0,0 +1,5 @@
+#!/bin/bash
+# Creates 10 schemas, scripts, data policies and behavior policies from the data-hub directory
+hmq data-hub start-trial
+hmq data-hub plan "$(dirname -- "$0")/data-hub"
+hmq data-hub apply "$(dirname -- "$0")/data-hub"
@@ -1,3 +1,3 @@
 function transform(publish, context) {
     return publish;
-}
\ No newline at end of file
+}
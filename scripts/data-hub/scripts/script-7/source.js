@@ -0,0 +1,3 @@
+function transform(publish, context) {
+    return publish;
+}
@@ -1,10 +1,15 @@
 use std::{fmt, fmt::Debug};
 
+use hivemq_openapi::models::Backup;
 use serde::{
     de::{self, Deserializer, Visitor},
     Deserialize, Serialize,
 };
 
+use crate::components::notifications::Notification;
+use crate::services::client_details_service::BulkResults;
+use crate::services::data_hub_sync_service::{ItemType, Plan};
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Action {
     Tick,
@@ -16,10 +21,12 @@ pub enum Action {
     Refresh,
     Error(String),
     Help,
+    Notify(Notification),
 
     ClosePopup,
     ConfirmPopup,
     FilterPopup,
+    SelectProfile,
 
     // Key Events
     PrevItem,
@@ -38,6 +45,13 @@ pub enum Action {
     CreateItem,
     UpdateItem,
     Filter,
+    OpenExternalEditor,
+    Search,
+    SearchNext,
+    SearchPrev,
+    /// Suspends the TUI to edit the text in an external editor
+    RunExternalEditor(String),
+    ExternalEditorClosed(Result<String, String>),
 
     SelectedItem(String),
 
@@ -59,12 +73,122 @@ pub enum Action {
         item_name: String,
         result: Result<String, String>,
     },
+    /// The policies were loaded to check which of them reference the item before it is deleted
+    ReferencingPoliciesLoaded {
+        item_name: String,
+        item_id: String,
+        result: Result<(), String>,
+    },
+    DownloadItem,
+    DownloadProgress {
+        item_name: String,
+        downloaded: u64,
+        total: Option<u64>,
+    },
+    DownloadFinished {
+        item_name: String,
+        result: Result<String, String>,
+    },
 
     // Clients view
-    ClientDetailsLoadingFinished(Result<(), String>),
+    /// The generation identifies the load, so results of a replaced load are ignored
+    ClientDetailsLoadingProgress {
+        generation: usize,
+        client_ids: Vec<String>,
+        fetched: usize,
+        total: usize,
+    },
+    ClientDetailsLoadingFinished {
+        generation: usize,
+        /// The errors of the clients that were skipped
+        result: Result<Vec<String>, String>,
+    },
+    ClientSubscriptionsLoadingFinished {
+        client_id: String,
+        result: Result<(), String>,
+    },
+    ClientStateLoadingFinished {
+        client_id: String,
+        result: Result<(), String>,
+    },
+    ShowClientsInState {
+        policy_id: String,
+        state_name: Option<String>,
+    },
+    ClientsInStateFound {
+        generation: usize,
+        filter: String,
+        result: Result<Vec<String>, String>,
+    },
+    DisconnectClient,
+    InvalidateClientSession,
+    ClientDisconnected(Result<String, String>),
+    ClientSessionInvalidated(Result<String, String>),
+    DisconnectAllClients,
+    InvalidateAllClientSessions,
+    ExportAllClients,
+    ClientBulkActionFinished {
+        title: String,
+        results: Result<BulkResults, String>,
+        /// The cached clients are outdated by the action and are reloaded
+        reload: bool,
+    },
+
+    // Data Hub
+    StartDataHubTrialMode,
+    DataHubTrialModeStarted(Result<(), String>),
+    ExportDataHub,
+    DataHubExported(Result<String, String>),
+    PlanDataHub,
+    DataHubPlanned(Result<Plan, String>),
+    ApplyDataHub(Plan),
+    DataHubApplied(Result<usize, String>),
+
+    GoToReference,
+    ShowItem {
+        item_type: ItemType,
+        id: String,
+    },
+
+    // Behavior Policies
+    FindClientsInState,
+
+    // Trace Recordings
+    StopTraceRecording,
+    TraceRecordingStopped(Result<String, String>),
 
     // Backups
     StartBackup,
+    RestoreBackup,
+    BackupRestoreStarted(Result<String, String>),
+    BackupRestoreFinished(Result<Backup, String>),
+
+    // Diagnostic Archives
+    CreateDiagnosticArchive,
+}
+
+impl Action {
+    /// Returns true if the action changes data on the broker.
+    /// These actions are rejected while a read-only profile is active.
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            Action::NewItem
+                | Action::Delete
+                | Action::CreateItem
+                | Action::UpdateItem
+                | Action::DisconnectClient
+                | Action::InvalidateClientSession
+                | Action::DisconnectAllClients
+                | Action::InvalidateAllClientSessions
+                | Action::StartDataHubTrialMode
+                | Action::ApplyDataHub(_)
+                | Action::StopTraceRecording
+                | Action::StartBackup
+                | Action::RestoreBackup
+                | Action::CreateDiagnosticArchive
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -72,6 +196,8 @@ pub enum ListWithDetailsAction {
     Delete(String),
     Create(String),
     Update(String),
+    /// Loads the policies before asking to delete the item, so no referencing policy is missed
+    LoadReferencingPolicies(String),
 }
 
 impl<'de> Deserialize<'de> for Action {
@@ -109,14 +235,33 @@ impl<'de> Deserialize<'de> for Action {
                     "Inspect" => Ok(Action::Inspect),
                     "Enter" => Ok(Action::Enter),
                     "CreateItem" => Ok(Action::CreateItem),
+                    "OpenExternalEditor" => Ok(Action::OpenExternalEditor),
+                    "Search" => Ok(Action::Search),
+                    "SearchNext" => Ok(Action::SearchNext),
+                    "SearchPrev" => Ok(Action::SearchPrev),
                     "UpdateItem" => Ok(Action::UpdateItem),
                     "Escape" => Ok(Action::Escape),
                     "NextTab" => Ok(Action::NextTab),
                     "PrevTab" => Ok(Action::PrevTab),
                     "Filter" => Ok(Action::Filter),
+                    "DownloadItem" => Ok(Action::DownloadItem),
                     "ClosePopup" => Ok(Action::ClosePopup),
                     "ConfirmPopup" => Ok(Action::ConfirmPopup),
+                    "SelectProfile" => Ok(Action::SelectProfile),
+                    "DisconnectClient" => Ok(Action::DisconnectClient),
+                    "InvalidateClientSession" => Ok(Action::InvalidateClientSession),
+                    "DisconnectAllClients" => Ok(Action::DisconnectAllClients),
+                    "InvalidateAllClientSessions" => Ok(Action::InvalidateAllClientSessions),
+                    "ExportAllClients" => Ok(Action::ExportAllClients),
+                    "ExportDataHub" => Ok(Action::ExportDataHub),
+                    "PlanDataHub" => Ok(Action::PlanDataHub),
+                    "GoToReference" => Ok(Action::GoToReference),
+                    "StartDataHubTrialMode" => Ok(Action::StartDataHubTrialMode),
+                    "FindClientsInState" => Ok(Action::FindClientsInState),
+                    "StopTraceRecording" => Ok(Action::StopTraceRecording),
                     "StartBackup" => Ok(Action::StartBackup),
+                    "RestoreBackup" => Ok(Action::RestoreBackup),
+                    "CreateDiagnosticArchive" => Ok(Action::CreateDiagnosticArchive),
                     "Tab1" => Ok(Action::SelectTab(0)),
                     "Tab2" => Ok(Action::SelectTab(1)),
                     "Tab3" => Ok(Action::SelectTab(2)),
@@ -124,6 +269,7 @@ impl<'de> Deserialize<'de> for Action {
                     "Tab5" => Ok(Action::SelectTab(4)),
                     "Tab6" => Ok(Action::SelectTab(5)),
                     "Tab7" => Ok(Action::SelectTab(6)),
+                    "Tab8" => Ok(Action::SelectTab(7)),
                     data if data.starts_with("Error(") => {
                         let error_msg = data.trim_start_matches("Error(").trim_end_matches(")");
                         Ok(Action::Error(error_msg.to_string()))
@@ -2,6 +2,7 @@ use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
 use ratatui::prelude::Rect;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
@@ -9,7 +10,8 @@ use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use crate::{
     action::Action,
     components::{fps::FpsCounter, home::Home, Component},
-    config::Config,
+    config::{Config, ProfileConfig},
+    external_editor,
     mode::Mode,
     tui,
 };
@@ -18,10 +20,10 @@ pub struct App {
     pub config: Config,
     pub tick_rate: f64,
     pub frame_rate: f64,
-    pub hivemq_address: String,
     pub components: Vec<Box<dyn Component>>,
     pub should_quit: bool,
     pub should_suspend: bool,
+    pub external_editor_text: Option<String>,
     pub mode: Rc<RefCell<Mode>>,
     pub last_tick_key_events: Vec<KeyEvent>,
     pub action_tx: UnboundedSender<Action>,
@@ -30,20 +32,24 @@ pub struct App {
 
 impl App {
     pub fn new(
+        config: Config,
         tick_rate: f64,
         frame_rate: f64,
-        hivemq_address: String,
+        profile: ProfileConfig,
+        client_details_concurrency: usize,
+        download_dir: PathBuf,
         is_debug: bool,
     ) -> Result<Self> {
-        let config = Config::new()?;
         let mode = Rc::new(RefCell::new(Mode::Home));
         let (action_tx, action_rx) = mpsc::unbounded_channel();
         let home = Home::new(
             action_tx.clone(),
             config.clone(),
-            hivemq_address.clone(),
+            profile,
+            client_details_concurrency,
+            download_dir,
             mode.clone(),
-        );
+        )?;
         let fps = FpsCounter::default();
         let mut components: Vec<Box<dyn Component>> = vec![Box::new(home)];
         if is_debug {
@@ -53,10 +59,10 @@ impl App {
         Ok(Self {
             tick_rate,
             frame_rate,
-            hivemq_address,
             components,
             should_quit: false,
             should_suspend: false,
+            external_editor_text: None,
             config,
             mode,
             last_tick_key_events: Vec::new(),
@@ -125,6 +131,9 @@ impl App {
                     Action::Quit => self.should_quit = true,
                     Action::Suspend => self.should_suspend = true,
                     Action::Resume => self.should_suspend = false,
+                    Action::RunExternalEditor(ref text) => {
+                        self.external_editor_text = Some(text.clone())
+                    }
                     Action::Resize(w, h) => {
                         tui.resize(Rect::new(0, 0, w, h))?;
                         tui.draw(|f| {
@@ -158,6 +167,15 @@ impl App {
                     };
                 }
             }
+            if let Some(text) = self.external_editor_text.take() {
+                tui.exit()?;
+                let result = external_editor::edit(&text);
+                tui = tui::Tui::new()?
+                    .tick_rate(self.tick_rate)
+                    .frame_rate(self.frame_rate);
+                tui.enter()?;
+                action_tx.send(Action::ExternalEditorClosed(result))?;
+            }
             if self.should_suspend {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
@@ -1,4 +1,9 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::config::{AppConfig, AuthConfig, ProfileConfig};
+use crate::hivemq_rest_client::TlsConfig;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
@@ -6,6 +11,7 @@ use clap::Parser;
 pub struct Cli {
     #[arg(
         long,
+        global = true,
         action = clap::ArgAction::HelpLong
     )]
     help: Option<bool>,
@@ -38,6 +44,7 @@ pub struct Cli {
     #[arg(
         short,
         long,
+        global = true,
         value_name = "STRING",
         help = "The HiveMQ hostname",
         default_value = "http://localhost"
@@ -47,9 +54,551 @@ pub struct Cli {
     #[arg(
         short,
         long,
+        global = true,
         value_name = "INTEGER",
         help = "The port of the HiveMQ Rest API",
         default_value_t = 8888
     )]
     pub port: usize,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "INTEGER",
+        help = "The maximum number of concurrent requests when loading client details",
+        default_value_t = 10
+    )]
+    pub client_details_concurrency: usize,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "The directory downloaded backups and trace recordings are stored in [default: the data directory]"
+    )]
+    pub download_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "STRING",
+        env = "HMQ_TUI_USERNAME",
+        help = "The username for basic authentication at the HiveMQ Rest API"
+    )]
+    pub username: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "STRING",
+        env = "HMQ_TUI_PASSWORD",
+        hide_env_values = true,
+        help = "The password for basic authentication at the HiveMQ Rest API"
+    )]
+    pub password: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "STRING",
+        env = "HMQ_TUI_TOKEN",
+        hide_env_values = true,
+        help = "The token for bearer authentication at the HiveMQ Rest API"
+    )]
+    pub token: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "A PEM file with additional CA certificates that are trusted for the HiveMQ Rest API"
+    )]
+    pub ca_cert: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        requires = "client_key",
+        help = "A PEM file with the client certificate for mutual TLS"
+    )]
+    pub client_cert: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        requires = "client_cert",
+        help = "A PEM file with the PKCS#8 private key of the client certificate"
+    )]
+    pub client_key: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Skips the verification of the HiveMQ Rest API's certificate. Only use this for testing"
+    )]
+    pub insecure: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Prevents any changes on the broker, e.g. deleting items or disconnecting clients"
+    )]
+    pub read_only: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "The connection profile from the config file to use instead of the connection arguments"
+    )]
+    pub profile: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "The output format of the commands"
+    )]
+    pub output: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Yaml,
+}
+
+/// Commands that are executed without starting the TUI, e.g. in scripts and CI pipelines.
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum Command {
+    /// Manages the connected MQTT clients
+    Clients {
+        #[command(subcommand)]
+        command: ClientsCommand,
+    },
+    /// Manages the Data Hub
+    DataHub {
+        #[command(subcommand)]
+        command: DataHubCommand,
+    },
+    /// Manages the Data Hub schemas
+    Schemas {
+        #[command(subcommand)]
+        command: DataHubItemCommand,
+    },
+    /// Manages the Data Hub scripts
+    Scripts {
+        #[command(subcommand)]
+        command: DataHubItemCommand,
+    },
+    /// Manages the Data Hub data policies
+    DataPolicies {
+        #[command(subcommand)]
+        command: PolicyCommand,
+    },
+    /// Manages the Data Hub behavior policies
+    BehaviorPolicies {
+        #[command(subcommand)]
+        command: PolicyCommand,
+    },
+    /// Manages the trace recordings
+    TraceRecordings {
+        #[command(subcommand)]
+        command: TraceRecordingsCommand,
+    },
+    /// Manages the backups
+    Backups {
+        #[command(subcommand)]
+        command: BackupsCommand,
+    },
+    /// Creates diagnostic archives
+    DiagnosticArchives {
+        #[command(subcommand)]
+        command: DiagnosticArchivesCommand,
+    },
+}
+
+impl Command {
+    /// Returns true if the command changes data on the broker.
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Command::Clients { .. } => false,
+            Command::DataHub { command } => {
+                matches!(
+                    command,
+                    DataHubCommand::StartTrial | DataHubCommand::Apply { .. }
+                )
+            }
+            Command::Schemas { command } | Command::Scripts { command } => matches!(
+                command,
+                DataHubItemCommand::Create { .. } | DataHubItemCommand::Delete { .. }
+            ),
+            Command::DataPolicies { command } | Command::BehaviorPolicies { command } => {
+                matches!(
+                    command,
+                    PolicyCommand::Apply { .. } | PolicyCommand::Delete { .. }
+                )
+            }
+            Command::TraceRecordings { command } => !matches!(
+                command,
+                TraceRecordingsCommand::List | TraceRecordingsCommand::Get { .. }
+            ),
+            Command::Backups { command } => !matches!(command, BackupsCommand::List),
+            Command::DiagnosticArchives { .. } => true,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum ClientsCommand {
+    /// Lists the details of all clients
+    List,
+    /// Prints the details of a client
+    Get { client_id: String },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum DataHubCommand {
+    /// Starts the Data Hub trial mode
+    StartTrial,
+    /// Writes all schemas, scripts, data policies and behavior policies into a directory with one file per item
+    Export { directory: PathBuf },
+    /// Shows the changes that make the broker match a directory in the layout of the export.
+    /// Kinds of items without a subdirectory are left unchanged
+    Plan { directory: PathBuf },
+    /// Applies the changes that make the broker match a directory in the layout of the export.
+    /// Schemas and scripts are created before the policies that reference them.
+    /// Kinds of items without a subdirectory are left unchanged
+    Apply { directory: PathBuf },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum DataHubItemCommand {
+    /// Lists all items
+    List,
+    /// Prints a single item
+    Get { id: String },
+    /// Creates an item, or a new version of it, from a JSON file
+    Create {
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            help = "The JSON file, - reads from stdin"
+        )]
+        file: PathBuf,
+    },
+    /// Deletes an item
+    Delete { id: String },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum PolicyCommand {
+    /// Lists all policies
+    List,
+    /// Prints a single policy
+    Get { id: String },
+    /// Creates the policy of a JSON file or updates it if it already exists
+    Apply {
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            help = "The JSON file, - reads from stdin"
+        )]
+        file: PathBuf,
+    },
+    /// Deletes a policy
+    Delete { id: String },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum TraceRecordingsCommand {
+    /// Lists all trace recordings
+    List,
+    /// Prints a single trace recording
+    Get { name: String },
+    /// Creates a trace recording from a JSON file
+    Create {
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            help = "The JSON file, - reads from stdin"
+        )]
+        file: PathBuf,
+    },
+    /// Stops a running trace recording
+    Stop { name: String },
+    /// Deletes a trace recording
+    Delete { name: String },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum BackupsCommand {
+    /// Lists all backups
+    List,
+    /// Starts a new backup
+    Create {
+        #[arg(long, help = "Waits until the backup is finished")]
+        wait: bool,
+    },
+    /// Restores a backup
+    Restore {
+        id: String,
+        #[arg(long, help = "Waits until the restore is finished")]
+        wait: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum DiagnosticArchivesCommand {
+    /// Creates a diagnostic archive
+    Create,
+}
+
+impl Cli {
+    /// Returns the profile given with `--profile` or a profile built from the other arguments.
+    /// Settings given as arguments or environment variables take precedence over the config file,
+    /// for a selected profile as well.
+    pub fn profile(&self, config: &AppConfig) -> Result<ProfileConfig, String> {
+        let profile = match &self.profile {
+            Some(name) => config
+                .find_profile(name)
+                .cloned()
+                .ok_or_else(|| format!("The profile '{name}' is not configured"))?,
+            None => {
+                let host = format!("{}:{}", self.host, self.port);
+                ProfileConfig {
+                    name: host.clone(),
+                    host,
+                    auth: config.auth.clone(),
+                    tls: config.tls.clone(),
+                    read_only: false,
+                }
+            }
+        };
+        Ok(self.override_profile(profile))
+    }
+
+    fn override_profile(&self, profile: ProfileConfig) -> ProfileConfig {
+        let auth = AuthConfig {
+            username: self.username.clone().or(profile.auth.username),
+            password: self.password.clone().or(profile.auth.password),
+            token: self.token.clone().or(profile.auth.token),
+        };
+        let (client_cert, client_key) = if self.client_cert.is_some() {
+            (self.client_cert.clone(), self.client_key.clone())
+        } else {
+            (profile.tls.client_cert, profile.tls.client_key)
+        };
+        let tls = TlsConfig {
+            ca_cert: self.ca_cert.clone().or(profile.tls.ca_cert),
+            client_cert,
+            client_key,
+            insecure: self.insecure || profile.tls.insecure,
+        };
+
+        ProfileConfig {
+            auth,
+            tls,
+            read_only: self.read_only || profile.read_only,
+            ..profile
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use std::path::PathBuf;
+
+    use crate::cli::{Cli, Command, OutputFormat, PolicyCommand};
+    use crate::config::{AppConfig, AuthConfig, ProfileConfig};
+    use crate::hivemq_rest_client::{Authentication, TlsConfig};
+
+    #[test]
+    fn test_connection_without_authentication() {
+        let cli = Cli::parse_from(["hmq", "--host", "http://broker", "--port", "9000"]);
+
+        let connection = cli
+            .profile(&AppConfig::default())
+            .unwrap()
+            .connection()
+            .unwrap();
+
+        assert_eq!("http://broker:9000", connection.host);
+        assert_eq!(None, connection.authentication);
+    }
+
+    #[test]
+    fn test_connection_arguments_override_config() {
+        let cli = Cli::parse_from(["hmq", "--username", "cli-user"]);
+        let config = AppConfig {
+            auth: AuthConfig {
+                username: Some("config-user".to_string()),
+                password: Some("config-password".to_string()),
+                token: None,
+            },
+            ..AppConfig::default()
+        };
+
+        let connection = cli.profile(&config).unwrap().connection().unwrap();
+
+        assert_eq!(
+            Some(Authentication::Basic {
+                username: "cli-user".to_string(),
+                password: Some("config-password".to_string()),
+            }),
+            connection.authentication
+        );
+    }
+
+    #[test]
+    fn test_connection_with_basic_and_bearer_authentication() {
+        let cli = Cli::parse_from(["hmq", "--token", "token"]);
+        let config = AppConfig {
+            auth: AuthConfig {
+                username: Some("user".to_string()),
+                password: None,
+                token: None,
+            },
+            ..AppConfig::default()
+        };
+
+        assert!(cli.profile(&config).unwrap().connection().is_err());
+    }
+
+    #[test]
+    fn test_connection_tls() {
+        let cli = Cli::parse_from([
+            "hmq",
+            "--client-cert",
+            "client.pem",
+            "--client-key",
+            "client-key.pem",
+        ]);
+        let config = AppConfig {
+            tls: TlsConfig {
+                ca_cert: Some(PathBuf::from("ca.pem")),
+                client_cert: Some(PathBuf::from("config-client.pem")),
+                client_key: Some(PathBuf::from("config-client-key.pem")),
+                insecure: true,
+            },
+            ..AppConfig::default()
+        };
+
+        let profile = cli.profile(&config).unwrap();
+
+        assert_eq!(
+            TlsConfig {
+                ca_cert: Some(PathBuf::from("ca.pem")),
+                client_cert: Some(PathBuf::from("client.pem")),
+                client_key: Some(PathBuf::from("client-key.pem")),
+                insecure: true,
+            },
+            profile.tls
+        );
+    }
+
+    #[test]
+    fn test_profile_from_config() {
+        let cli = Cli::parse_from(["hmq", "--profile", "staging"]);
+        let staging = ProfileConfig {
+            name: "staging".to_string(),
+            host: "https://staging:8888".to_string(),
+            auth: AuthConfig::default(),
+            tls: TlsConfig::default(),
+            read_only: true,
+        };
+        let config = AppConfig {
+            profiles: vec![staging.clone()],
+            ..AppConfig::default()
+        };
+
+        assert_eq!(staging, cli.profile(&config).unwrap());
+        assert!(Cli::parse_from(["hmq", "--profile", "prod"])
+            .profile(&config)
+            .is_err());
+    }
+
+    #[test]
+    fn test_profile_with_overrides() {
+        let cli = Cli::parse_from([
+            "hmq",
+            "--profile",
+            "staging",
+            "--read-only",
+            "--token",
+            "cli-token",
+            "--insecure",
+        ]);
+        let config = AppConfig {
+            profiles: vec![ProfileConfig {
+                name: "staging".to_string(),
+                host: "https://staging:8888".to_string(),
+                auth: AuthConfig {
+                    username: None,
+                    password: None,
+                    token: Some("config-token".to_string()),
+                },
+                tls: TlsConfig {
+                    ca_cert: Some(PathBuf::from("ca.pem")),
+                    ..TlsConfig::default()
+                },
+                read_only: false,
+            }],
+            ..AppConfig::default()
+        };
+
+        let profile = cli.profile(&config).unwrap();
+
+        assert!(profile.read_only);
+        assert_eq!("https://staging:8888", profile.host);
+        assert_eq!(Some("cli-token".to_string()), profile.auth.token);
+        assert_eq!(
+            TlsConfig {
+                ca_cert: Some(PathBuf::from("ca.pem")),
+                client_cert: None,
+                client_key: None,
+                insecure: true,
+            },
+            profile.tls
+        );
+    }
+
+    #[test]
+    fn test_subcommand_with_global_args() {
+        let cli = Cli::parse_from([
+            "hmq",
+            "data-policies",
+            "apply",
+            "-f",
+            "policy.json",
+            "--profile",
+            "staging",
+            "-o",
+            "json",
+        ]);
+
+        assert_eq!(
+            Some(Command::DataPolicies {
+                command: PolicyCommand::Apply {
+                    file: PathBuf::from("policy.json")
+                }
+            }),
+            cli.command
+        );
+        assert_eq!(Some("staging".to_string()), cli.profile);
+        assert_eq!(OutputFormat::Json, cli.output);
+        assert!(cli.command.unwrap().is_mutating());
+    }
 }
@@ -11,7 +11,9 @@ pub mod editor;
 pub mod fps;
 pub mod home;
 pub mod list_with_details;
+pub mod notifications;
 pub mod popups;
+pub mod references_pane;
 pub mod tabs;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
@@ -38,6 +40,14 @@ pub trait Component {
     fn activate(&mut self) -> Result<()> {
         Ok(())
     }
+    /// Deactivates the component, e.g. when another tab is selected.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An Ok result or an error.
+    fn deactivate(&mut self) -> Result<()> {
+        Ok(())
+    }
     /// Handle incoming events and produce actions if necessary.
     ///
     /// # Arguments
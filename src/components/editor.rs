@@ -3,28 +3,74 @@ use std::iter::FromIterator;
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
-    layout::Rect,
-    style::{Color, Style, Stylize},
-    widgets::{block::Block, Borders},
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    widgets::{block::Block, Borders, Paragraph},
 };
+use regex::Regex;
 use tui::Frame;
 use tui_textarea::{CursorMove, TextArea};
+use unicode_width::UnicodeWidthChar;
 
-use crate::{action::Action, components::Component, tui};
+use crate::{action::Action, components::Component, theme::theme, tui};
+
+use self::json_highlight::{matching_bracket, tokenize, TokenKind};
+
+mod json_highlight;
 
 pub struct Editor<'a> {
     textarea: TextArea<'a>,
     title: String,
     readonly: bool,
+    viewport: Viewport,
+    validation: Option<fn(&str) -> serde_json::Result<()>>,
+    error: Option<serde_json::Error>,
+    search: Option<Search>,
+}
+
+/// An incremental, case-insensitive search of the text.
+struct Search {
+    query: String,
+    /// The cursor position the search started at, which is restored when it is cancelled
+    origin: (usize, usize),
+    /// Whether the query is being typed
+    editing: bool,
+    regex: Option<Regex>,
+}
+
+/// The scroll position of the textarea.
+/// It is calculated the same way the textarea does when it is rendered,
+/// to know where the characters of the text are rendered and highlight them.
+#[derive(Default)]
+struct Viewport {
+    top_row: u16,
+    top_col: u16,
+    height: u16,
+}
+
+impl Viewport {
+    /// Scrolls so that the cursor is visible in an area of the given size.
+    fn scroll(&mut self, (row, col): (usize, usize), width: u16, height: u16) {
+        fn next_scroll_top(prev_top: u16, cursor: u16, length: u16) -> u16 {
+            if cursor < prev_top {
+                cursor
+            } else if prev_top + length <= cursor {
+                cursor + 1 - length
+            } else {
+                prev_top
+            }
+        }
+
+        self.top_row = next_scroll_top(self.top_row, row as u16, height);
+        self.top_col = next_scroll_top(self.top_col, col as u16, width);
+        self.height = height;
+    }
 }
 
 impl Editor<'_> {
     fn new(readonly: bool, text: String, title: String) -> Self {
-        let mut textarea = TextArea::from_iter(text.lines());
-        textarea.set_line_number_style(Style::default().dim());
-        textarea.set_cursor_line_style(Style::default().not_underlined());
-        textarea.move_cursor(CursorMove::Top);
-
+        let mut textarea = Editor::new_textarea(&text);
         textarea.set_block(
             Block::default()
                 .borders(Borders::ALL)
@@ -35,9 +81,169 @@ impl Editor<'_> {
             textarea,
             title,
             readonly,
+            viewport: Viewport::default(),
+            validation: None,
+            error: None,
+            search: None,
+        }
+    }
+
+    fn new_textarea(text: &str) -> TextArea<'static> {
+        let mut textarea = TextArea::from_iter(text.lines());
+        textarea.set_line_number_style(Style::default().dim());
+        textarea.set_cursor_line_style(Style::default().not_underlined());
+        textarea.set_search_style(theme().search_match);
+        textarea.move_cursor(CursorMove::Top);
+        textarea
+    }
+
+    /// Validates the text whenever it changes, e.g. by deserializing it into the item type.
+    /// The position and message of the error are shown in the editor.
+    pub fn with_validation(mut self, validation: fn(&str) -> serde_json::Result<()>) -> Self {
+        self.validation = Some(validation);
+        self.validate();
+        self
+    }
+
+    pub fn is_readonly(&self) -> bool {
+        self.readonly
+    }
+
+    /// Replaces the text, e.g. with the text edited in an external editor.
+    pub fn set_text(&mut self, text: &str) {
+        let mut textarea = Editor::new_textarea(text);
+        textarea.set_style(self.textarea.style());
+        textarea.set_cursor_style(self.textarea.cursor_style());
+        if let Some(block) = self.textarea.block() {
+            textarea.set_block(block.clone());
+        }
+        self.textarea = textarea;
+        self.viewport = Viewport::default();
+        self.validate();
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search.as_ref().is_some_and(|search| search.editing)
+    }
+
+    /// Starts typing a new search query, which is searched for while it is typed.
+    pub fn start_search(&mut self) {
+        self.search = Some(Search {
+            query: String::new(),
+            origin: self.textarea.cursor(),
+            editing: true,
+            regex: None,
+        });
+        self.update_search();
+    }
+
+    /// Stops typing the search query.
+    /// The matches stay highlighted if it is confirmed, otherwise the search is cleared.
+    pub fn end_search(&mut self, confirm: bool) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        if confirm && !search.query.is_empty() {
+            search.editing = false;
+            return;
+        }
+        let (row, col) = search.origin;
+        self.search = None;
+        self.update_search();
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+    }
+
+    pub fn search_next(&mut self) {
+        if self.search.is_some() {
+            self.textarea.search_forward(false);
+        }
+    }
+
+    pub fn search_prev(&mut self) {
+        if self.search.is_some() {
+            self.textarea.search_back(false);
+        }
+    }
+
+    /// Highlights the matches of the query and moves to the first match after the origin of the search.
+    fn update_search(&mut self) {
+        let Some(search) = &mut self.search else {
+            let _ = self.textarea.set_search_pattern("");
+            return;
+        };
+        let pattern = if search.query.is_empty() {
+            String::new()
+        } else {
+            format!("(?i){}", regex::escape(&search.query))
+        };
+        search.regex = Regex::new(&pattern).ok().filter(|_| !pattern.is_empty());
+        let _ = self.textarea.set_search_pattern(&pattern);
+
+        let (row, col) = search.origin;
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+        if !pattern.is_empty() {
+            self.textarea.search_forward(true);
         }
     }
 
+    /// Returns the query and the number of the match at the cursor of all matches, e.g. `/id [2/5]`.
+    fn search_status(&self) -> Option<String> {
+        let search = self.search.as_ref()?;
+        let Some(regex) = &search.regex else {
+            return Some(format!("/{}", search.query));
+        };
+        let matches: Vec<(usize, usize)> = self
+            .textarea
+            .lines()
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                regex
+                    .find_iter(line)
+                    .map(move |found| (row, line[..found.start()].chars().count()))
+            })
+            .collect();
+        if matches.is_empty() {
+            return Some(format!("/{} [no matches]", search.query));
+        }
+        let cursor = self.textarea.cursor();
+        let current = matches
+            .iter()
+            .position(|position| *position == cursor)
+            .map_or("-".to_string(), |index| (index + 1).to_string());
+        Some(format!("/{} [{current}/{}]", search.query, matches.len()))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn error(&self) -> Option<&serde_json::Error> {
+        self.error.as_ref()
+    }
+
+    fn validate(&mut self) {
+        if let Some(validation) = self.validation {
+            self.error = validation(&self.get_text()).err();
+        }
+    }
+
+    /// Returns the row and char index of the error.
+    fn error_position(&self) -> Option<(usize, usize)> {
+        let error = self.error.as_ref()?;
+        let row = error.line().checked_sub(1)?;
+        let line = self.textarea.lines().get(row)?;
+        // The column of the error counts bytes, starting with 1
+        let byte = error.column().saturating_sub(1);
+        let col = line
+            .char_indices()
+            .take_while(|(index, _)| *index < byte)
+            .count();
+        Some((row, col))
+    }
+
     pub fn readonly(text: String, title: String) -> Self {
         Editor::new(true, text, title)
     }
@@ -57,8 +263,7 @@ impl Editor<'_> {
                 .borders(Borders::ALL)
                 .title(self.title.to_owned()),
         );
-        self.textarea
-            .set_cursor_style(Style::default().bg(Color::Blue));
+        self.textarea.set_cursor_style(theme().editor_cursor);
     }
 
     pub fn unfocus(&mut self) {
@@ -75,10 +280,136 @@ impl Editor<'_> {
     pub fn get_text(&self) -> String {
         self.textarea.lines().join("\n")
     }
+
+    /// Moves the cursor by a page instead of scrolling the textarea,
+    /// so the scroll position stays the one calculated by the [Viewport].
+    fn move_page(&mut self, down: bool) {
+        let (row, col) = self.textarea.cursor();
+        let height = self.viewport.height as usize;
+        let row = if down {
+            row + height
+        } else {
+            row.saturating_sub(height)
+        };
+        self.textarea
+            .move_cursor(CursorMove::Jump(row as u16, col as u16));
+    }
+
+    /// Highlights the JSON tokens of the visible lines in the rendered textarea.
+    fn highlight(&self, buf: &mut Buffer, inner: Rect) {
+        let lines = self.textarea.lines();
+        // The line numbers are right aligned with a space before and after them
+        let gutter = lines.len().to_string().len() + 2;
+        let theme = theme();
+
+        let visible_rows = (self.viewport.top_row as usize..lines.len())
+            .take(inner.height as usize)
+            .enumerate();
+        for (y, row) in visible_rows {
+            let line = &lines[row];
+            let columns = display_columns(line, self.textarea.tab_length());
+            for token in tokenize(line) {
+                let style = match token.kind {
+                    TokenKind::Key => theme.json_key,
+                    TokenKind::String => theme.json_string,
+                    TokenKind::Number => theme.json_number,
+                    TokenKind::Literal => theme.json_literal,
+                    TokenKind::Punctuation => theme.json_punctuation,
+                };
+                for column in &columns[token.start..token.end] {
+                    self.set_style(buf, inner, y, gutter + column, style);
+                }
+            }
+            // The tokens must not hide the matches highlighted by the textarea
+            if let Some(regex) = self
+                .search
+                .as_ref()
+                .and_then(|search| search.regex.as_ref())
+            {
+                for found in regex.find_iter(line) {
+                    let start = line[..found.start()].chars().count();
+                    let end = start + found.as_str().chars().count();
+                    for column in &columns[start..end] {
+                        self.set_style(buf, inner, y, gutter + column, theme.search_match);
+                    }
+                }
+            }
+        }
+
+        if let Some((row, col)) = self.error_position() {
+            if let Some(y) = row.checked_sub(self.viewport.top_row as usize) {
+                for column in 0..gutter {
+                    self.set_style(buf, inner, y, column, theme.error);
+                }
+                // Errors at the end of a line are shown after its last character
+                let columns =
+                    display_columns(&format!("{} ", lines[row]), self.textarea.tab_length());
+                self.set_style(buf, inner, y, gutter + columns[col], theme.json_error);
+            }
+        }
+
+        let cursor = self.textarea.cursor();
+        if let Some(matching) = matching_bracket(lines, cursor) {
+            for (row, col) in [cursor, matching] {
+                let Some(y) = row.checked_sub(self.viewport.top_row as usize) else {
+                    continue;
+                };
+                let columns = display_columns(&lines[row], self.textarea.tab_length());
+                self.set_style(
+                    buf,
+                    inner,
+                    y,
+                    gutter + columns[col],
+                    theme.json_bracket_match,
+                );
+            }
+        }
+    }
+
+    /// Sets the style of the cell of a column of a visible line, if the column is not scrolled out.
+    fn set_style(&self, buf: &mut Buffer, inner: Rect, y: usize, column: usize, style: Style) {
+        let Some(x) = column.checked_sub(self.viewport.top_col as usize) else {
+            return;
+        };
+        if x < inner.width as usize && y < inner.height as usize {
+            buf.get_mut(inner.x + x as u16, inner.y + y as u16)
+                .set_style(style);
+        }
+    }
+}
+
+/// Returns the column each character of a line is rendered at, with tabs expanded like the textarea does.
+fn display_columns(line: &str, tab_len: u8) -> Vec<usize> {
+    let tab_len = tab_len as usize;
+    let mut column = 0;
+    line.chars()
+        .map(|c| {
+            let start = column;
+            column += match c {
+                '\t' if tab_len > 0 => tab_len - column % tab_len,
+                c => c.width().unwrap_or_default(),
+            };
+            start
+        })
+        .collect()
 }
 
 impl Component for Editor<'_> {
     fn handle_key_events(&mut self, mut key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(search) = self.search.as_mut().filter(|search| search.editing) {
+            match key.code {
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    search.query.push(c)
+                }
+                KeyCode::Backspace => {
+                    search.query.pop();
+                }
+                _ => return Ok(None),
+            }
+            self.update_search();
+            return Ok(None);
+        }
+
         if self.readonly {
             match key.code {
                 KeyCode::Left => {}
@@ -119,11 +450,43 @@ impl Component for Editor<'_> {
             }
         }
 
-        self.textarea.input(key);
+        match (key.code, key.modifiers) {
+            (KeyCode::PageDown, _) => self.move_page(true),
+            (KeyCode::PageUp, _) => self.move_page(false),
+            (KeyCode::Char('v'), KeyModifiers::CONTROL) => self.move_page(true),
+            (KeyCode::Char('v'), KeyModifiers::ALT) => self.move_page(false),
+            _ => {
+                if self.textarea.input(key) {
+                    self.validate();
+                }
+            }
+        }
         Ok(None)
     }
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let status = match &self.error {
+            Some(error) => Some(Paragraph::new(error.to_string()).style(theme().error)),
+            None => self
+                .search_status()
+                .map(|status| Paragraph::new(status).style(theme().accent)),
+        };
+        let area = match status {
+            Some(status) => {
+                let layout =
+                    Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).split(area);
+                f.render_widget(status, layout[1]);
+                layout[0]
+            }
+            None => area,
+        };
+        let inner = self
+            .textarea
+            .block()
+            .map_or(area, |block| block.inner(area));
+        self.viewport
+            .scroll(self.textarea.cursor(), inner.width, inner.height);
         f.render_widget(self.textarea.widget(), area);
+        self.highlight(f.buffer_mut(), inner);
         Ok(())
     }
 }
@@ -0,0 +1,212 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+    Key,
+    String,
+    Number,
+    /// `true`, `false` and `null`
+    Literal,
+    Punctuation,
+}
+
+/// A token of a line from the char index `start` to the char index `end` (exclusive).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Splits a line of JSON into tokens.
+/// Strings that are not closed end with the line and unknown characters are skipped,
+/// so incomplete JSON is highlighted while it is typed.
+pub fn tokenize(line: &str) -> Vec<Token> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let kind = match chars[i] {
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(chars.len());
+                match chars[i..].iter().find(|c| !c.is_whitespace()) {
+                    Some(':') => TokenKind::Key,
+                    _ => TokenKind::String,
+                }
+            }
+            '{' | '}' | '[' | ']' | ',' | ':' => {
+                i += 1;
+                TokenKind::Punctuation
+            }
+            '-' | '0'..='9' => {
+                while i < chars.len()
+                    && (chars[i].is_ascii_digit()
+                        || matches!(chars[i], '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            c if c.is_ascii_alphabetic() => {
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if !matches!(word.as_str(), "true" | "false" | "null") {
+                    continue;
+                }
+                TokenKind::Literal
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+    tokens
+}
+
+/// Returns the position (row, char index) of the bracket matching the bracket at the position.
+/// Brackets in strings are ignored.
+pub fn matching_bracket(lines: &[String], (row, col): (usize, usize)) -> Option<(usize, usize)> {
+    let bracket = brackets(lines.get(row)?)
+        .into_iter()
+        .find(|(index, _)| *index == col)?
+        .1;
+    let (opening, closing) = match bracket {
+        '{' | '}' => ('{', '}'),
+        _ => ('[', ']'),
+    };
+    let forward = bracket == opening;
+
+    let mut depth = 0;
+    let mut check = |row: usize, (index, c): (usize, char)| {
+        if c == bracket {
+            depth += 1;
+        } else if c == opening || c == closing {
+            if depth == 0 {
+                return Some((row, index));
+            }
+            depth -= 1;
+        }
+        None
+    };
+
+    if forward {
+        for (r, line) in lines.iter().enumerate().skip(row) {
+            for found in brackets(line) {
+                if r == row && found.0 <= col {
+                    continue;
+                }
+                if let Some(position) = check(r, found) {
+                    return Some(position);
+                }
+            }
+        }
+    } else {
+        for (r, line) in lines.iter().enumerate().take(row + 1).rev() {
+            for found in brackets(line).into_iter().rev() {
+                if r == row && found.0 >= col {
+                    continue;
+                }
+                if let Some(position) = check(r, found) {
+                    return Some(position);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Returns the char indexes and characters of the brackets of a line outside of strings.
+fn brackets(line: &str) -> Vec<(usize, char)> {
+    let chars: Vec<char> = line.chars().collect();
+    tokenize(line)
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Punctuation)
+        .map(|token| (token.start, chars[token.start]))
+        .filter(|(_, c)| matches!(c, '{' | '}' | '[' | ']'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{matching_bracket, tokenize, TokenKind};
+
+    fn kinds(line: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(line)
+            .into_iter()
+            .map(|token| {
+                let start = line.char_indices().nth(token.start).unwrap().0;
+                let end = line
+                    .char_indices()
+                    .nth(token.end)
+                    .map_or(line.len(), |(index, _)| index);
+                (token.kind, &line[start..end])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            vec![
+                (TokenKind::Key, r#""id""#),
+                (TokenKind::Punctuation, ":"),
+                (TokenKind::String, r#""a \"b\" ü""#),
+                (TokenKind::Punctuation, ","),
+            ],
+            kinds(r#"  "id": "a \"b\" ü","#)
+        );
+        assert_eq!(
+            vec![
+                (TokenKind::Punctuation, "["),
+                (TokenKind::Number, "-1.5e+3"),
+                (TokenKind::Punctuation, ","),
+                (TokenKind::Literal, "true"),
+                (TokenKind::Punctuation, ","),
+                (TokenKind::Literal, "null"),
+                (TokenKind::Punctuation, ","),
+                (TokenKind::Punctuation, "]"),
+            ],
+            kinds("[-1.5e+3, true, null, invalid]")
+        );
+        assert_eq!(
+            vec![(TokenKind::String, r#""not closed"#)],
+            kinds(r#""not closed"#)
+        );
+    }
+
+    #[test]
+    fn test_matching_bracket() {
+        let lines: Vec<String> = [
+            "{",
+            r#"  "ids": ["a", "]"],"#,
+            r#"  "nested": { "b": [] }"#,
+            "}",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+
+        assert_eq!(Some((3, 0)), matching_bracket(&lines, (0, 0)));
+        assert_eq!(Some((0, 0)), matching_bracket(&lines, (3, 0)));
+        assert_eq!(Some((1, 18)), matching_bracket(&lines, (1, 9)));
+        assert_eq!(Some((2, 12)), matching_bracket(&lines, (2, 22)));
+        assert_eq!(None, matching_bracket(&lines, (1, 16)));
+        assert_eq!(None, matching_bracket(&lines, (1, 2)));
+    }
+}
@@ -1,96 +1,394 @@
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
 
-use color_eyre::eyre::{Ok, Result};
-use crossterm::event::{KeyEvent, MouseEvent};
+use color_eyre::eyre::{eyre, Ok, Result};
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent};
 use ratatui::{prelude::*, widgets::*};
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_util::sync::CancellationToken;
 
+use crate::components::popups::input_popup::InputPopup;
+use crate::components::popups::plan_popup::PlanPopup;
+use crate::components::popups::profile_popup::ProfilePopup;
+use crate::components::popups::Popup;
+use crate::config::ProfileConfig;
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::services::data_hub_export_service::DataHubExportService;
+use crate::services::data_hub_service::DataHubService;
+use crate::services::data_hub_sync_service::{DataHubSyncService, ItemType, Plan};
 use crate::sqlite::init_sqlite;
+use crate::theme::theme;
 use crate::{
     action::Action,
     components::tabs::{
-        backups::BackupsTab, behavior_policies::BehaviorPoliciesTab, clients::Clients,
-        data_policies::DataPoliciesTab, schemas::SchemasTab, scripts::ScriptsTab,
-        trace_recordings::TraceRecordingsTab, TabComponent,
+        backups, backups::BackupsTab, behavior_policies::BehaviorPoliciesTab, clients,
+        clients::Clients, data_policies::DataPoliciesTab,
+        diagnostic_archives::DiagnosticArchivesTab, schemas::SchemasTab, scripts::ScriptsTab,
+        trace_recordings, trace_recordings::TraceRecordingsTab, TabComponent,
     },
     config::Config,
     mode::Mode,
     tui::Event,
 };
 
+use super::notifications::{Notification, Notifications};
 use super::{Component, Frame};
 
+/// The index of the clients tab, which receives the actions that show clients from other tabs.
+const CLIENTS_TAB: usize = 0;
+/// The indexes of the Data Hub tabs, which are reloaded after the Data Hub trial mode was started
+/// or a Data Hub plan was applied.
+const DATA_HUB_TABS: [usize; 4] = [
+    SCHEMAS_TAB,
+    SCRIPTS_TAB,
+    DATA_POLICIES_TAB,
+    BEHAVIOR_POLICIES_TAB,
+];
+/// The indexes of the Data Hub tabs that show the items of a reference.
+const SCHEMAS_TAB: usize = 1;
+const SCRIPTS_TAB: usize = 2;
+const DATA_POLICIES_TAB: usize = 3;
+const BEHAVIOR_POLICIES_TAB: usize = 4;
+
+enum HomePopup {
+    Profiles(ProfilePopup),
+    ExportDataHub(Box<InputPopup<'static>>),
+    PlanDataHub(Box<InputPopup<'static>>),
+    Plan(PlanPopup),
+}
+
 pub struct Home {
     action_tx: UnboundedSender<Action>,
     config: Config,
     mode: Rc<RefCell<Mode>>,
-    tabs: [Box<dyn TabComponent>; 7],
+    tabs: [Box<dyn TabComponent>; 8],
     active_tab: usize,
+    notifications: Notifications,
+    data_hub_service: Arc<DataHubService>,
+    profile: ProfileConfig,
+    popup: Option<HomePopup>,
+    connection: HivemqConnection,
+    /// Sends the results of tasks for the current profile, which are discarded after a profile switch
+    profile_action_tx: UnboundedSender<Action>,
+    /// Cancelled when the profile is switched to discard the results of the tasks of the previous tabs
+    tabs_cancellation_token: CancellationToken,
+    client_details_concurrency: usize,
+    download_dir: PathBuf,
 }
 
 impl Home {
     pub fn new(
         action_tx: UnboundedSender<Action>,
         config: Config,
-        hivemq_address: String,
+        profile: ProfileConfig,
+        client_details_concurrency: usize,
+        download_dir: PathBuf,
         mode: Rc<RefCell<Mode>>,
-    ) -> Self {
-        let sqlite_pool = init_sqlite();
-        return Home {
-            action_tx: action_tx.clone(),
+    ) -> Result<Self> {
+        let connection = profile.connection().map_err(|err| eyre!(err))?;
+        let tabs_cancellation_token = CancellationToken::new();
+        let profile_action_tx = forward_tab_actions(&action_tx, tabs_cancellation_token.clone());
+        let tabs = create_tabs(
+            &profile_action_tx,
+            &connection,
+            client_details_concurrency,
+            &download_dir,
+            &mode,
+        );
+        Ok(Home {
+            action_tx,
             config,
-            mode: mode.clone(),
-            tabs: [
-                Box::new(Clients::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-                Box::new(SchemasTab::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-                Box::new(ScriptsTab::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-                Box::new(DataPoliciesTab::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-                Box::new(BehaviorPoliciesTab::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-                Box::new(TraceRecordingsTab::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-                Box::new(BackupsTab::new(
-                    action_tx.clone(),
-                    hivemq_address.to_owned(),
-                    mode.clone(),
-                    &sqlite_pool,
-                )),
-            ],
+            mode,
+            tabs,
             active_tab: 0,
+            notifications: Notifications::default(),
+            data_hub_service: Arc::new(DataHubService::new(&connection)),
+            connection,
+            profile_action_tx,
+            tabs_cancellation_token,
+            profile,
+            popup: None,
+            client_details_concurrency,
+            download_dir,
+        })
+    }
+
+    /// Connects to the broker of the given profile.
+    /// All tabs are recreated with an empty cache, so no items of the previous broker are shown.
+    fn switch_profile(&mut self, profile: ProfileConfig) -> Result<()> {
+        let connection = match profile.connection() {
+            std::result::Result::Ok(connection) => connection,
+            Err(message) => {
+                self.notifications.push(Notification::error(
+                    format!("Switching to '{}' failed", profile.name),
+                    message,
+                ));
+                return Ok(());
+            }
         };
+
+        for tab in self.tabs.iter_mut() {
+            tab.deactivate()?;
+        }
+        self.tabs_cancellation_token.cancel();
+        self.tabs_cancellation_token = CancellationToken::new();
+        self.profile_action_tx =
+            forward_tab_actions(&self.action_tx, self.tabs_cancellation_token.clone());
+        self.tabs = create_tabs(
+            &self.profile_action_tx,
+            &connection,
+            self.client_details_concurrency,
+            &self.download_dir,
+            &self.mode,
+        );
+        self.data_hub_service = Arc::new(DataHubService::new(&connection));
+        self.connection = connection;
+        self.tabs[self.active_tab].activate()?;
+        self.notifications.push(Notification::info(
+            "Profile switched".to_string(),
+            format!("Connected to '{}' ({})", profile.name, profile.host),
+        ));
+        self.profile = profile;
+        Ok(())
+    }
+
+    fn open_profile_popup(&mut self) {
+        self.popup = Some(HomePopup::Profiles(ProfilePopup::new(
+            self.config.config.profiles.clone(),
+            self.profile.name.clone(),
+        )));
+        self.mode.replace(Mode::ProfilePopup);
+    }
+
+    fn open_export_popup(&mut self) {
+        let default_dir = self.default_export_dir();
+        self.popup = Some(HomePopup::ExportDataHub(Box::new(InputPopup::new(
+            "Export Data Hub".to_string(),
+            "Enter the directory the schemas, scripts and policies are written to. \
+            Previously exported files in it are replaced. \
+            Leave it empty to use the directory below."
+                .to_string(),
+            &default_dir.display().to_string(),
+        ))));
+        self.mode.replace(Mode::InputPopup);
+    }
+
+    fn open_plan_input_popup(&mut self) {
+        let default_dir = self.default_export_dir();
+        self.popup = Some(HomePopup::PlanDataHub(Box::new(InputPopup::new(
+            "Plan Data Hub".to_string(),
+            "Enter the directory with the schemas, scripts and policies in the layout of the export. \
+            The changes that make the broker match it are shown before they are applied. \
+            Leave it empty to use the directory below."
+                .to_string(),
+            &default_dir.display().to_string(),
+        ))));
+        self.mode.replace(Mode::InputPopup);
+    }
+
+    fn open_plan_popup(&mut self, plan: Plan) {
+        if plan.is_empty() {
+            self.notifications.push(Notification::info(
+                "Data Hub plan".to_string(),
+                "The broker already matches the directory".to_string(),
+            ));
+            return;
+        }
+        self.popup = Some(HomePopup::Plan(PlanPopup::new(
+            plan,
+            self.connection.clone(),
+        )));
+        self.mode.replace(Mode::PlanPopup);
+    }
+
+    fn close_popup(&mut self) -> Result<()> {
+        self.popup = None;
+        self.tabs[self.active_tab].activate()
+    }
+
+    fn default_export_dir(&self) -> PathBuf {
+        self.download_dir.join("data-hub")
+    }
+
+    fn export_data_hub(&self, directory: PathBuf) {
+        let service = DataHubExportService::new(&self.connection);
+        let tx = self.profile_action_tx.clone();
+        tokio::spawn(async move {
+            let result = service
+                .export(&directory)
+                .await
+                .map(|summary| format!("Exported {summary} to {}", directory.display()));
+            tx.send(Action::DataHubExported(result)).unwrap();
+        });
+    }
+
+    fn plan_data_hub(&self, directory: PathBuf) {
+        let service = DataHubSyncService::new(&self.connection);
+        let tx = self.profile_action_tx.clone();
+        tokio::spawn(async move {
+            let result = service.plan(&directory).await;
+            tx.send(Action::DataHubPlanned(result)).unwrap();
+        });
+    }
+
+    fn apply_data_hub(&self, plan: Plan, connection: &HivemqConnection) {
+        let service = DataHubSyncService::new(connection);
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let result = service.apply(&plan).await;
+            tx.send(Action::DataHubApplied(result)).unwrap();
+        });
+    }
+
+    /// Returns the entered directory or the default one if nothing was entered.
+    fn input_dir(&self, input: String) -> PathBuf {
+        if input.is_empty() {
+            self.default_export_dir()
+        } else {
+            PathBuf::from(input)
+        }
+    }
+
+    fn notify_data_hub_exported(&mut self, result: std::result::Result<String, String>) {
+        let notification = match result {
+            std::result::Result::Ok(message) => {
+                Notification::info("Data Hub exported".to_string(), message)
+            }
+            Err(message) => Notification::error("Exporting Data Hub failed".to_string(), message),
+        };
+        self.notifications.push(notification);
+    }
+
+    fn notify_data_hub_applied(
+        &mut self,
+        result: std::result::Result<usize, String>,
+    ) -> Result<()> {
+        // Failed plans may have been applied partially
+        for tab in DATA_HUB_TABS {
+            self.update_tab(tab, Action::LoadAllItems)?;
+        }
+        let notification = match result {
+            std::result::Result::Ok(count) => Notification::info(
+                "Data Hub applied".to_string(),
+                format!("Applied {count} changes"),
+            ),
+            Err(message) => Notification::error("Applying Data Hub failed".to_string(), message),
+        };
+        self.notifications.push(notification);
+        Ok(())
+    }
+
+    fn notify_read_only(&mut self) {
+        self.notifications.push(Notification::error(
+            "Read-only profile".to_string(),
+            format!(
+                "Changes are not allowed with the profile '{}'",
+                self.profile.name
+            ),
+        ));
+    }
+
+    /// Handles the action in the open popup.
+    /// Returns the action if no popup is open or the popup does not handle it,
+    /// so the results of running tasks still reach the tabs while a popup is shown.
+    fn update_popup(&mut self, action: Action) -> Result<Option<Action>> {
+        let Some(popup) = &mut self.popup else {
+            return Ok(Some(action));
+        };
+        match (popup, action) {
+            (HomePopup::Profiles(popup), Action::PrevItem) => popup.prev_item(),
+            (HomePopup::Profiles(popup), Action::NextItem) => popup.next_item(),
+            (_, Action::ClosePopup) => self.close_popup()?,
+            (HomePopup::Profiles(popup), Action::ConfirmPopup) => {
+                let selected = popup.get_selected().cloned();
+                self.close_popup()?;
+                if let Some(profile) = selected {
+                    self.switch_profile(profile)?;
+                }
+            }
+            (HomePopup::ExportDataHub(popup), Action::ConfirmPopup) => {
+                let input = popup.get_input();
+                let directory = self.input_dir(input);
+                self.close_popup()?;
+                self.export_data_hub(directory);
+            }
+            (HomePopup::PlanDataHub(popup), Action::ConfirmPopup) => {
+                let input = popup.get_input();
+                let directory = self.input_dir(input);
+                self.close_popup()?;
+                self.plan_data_hub(directory);
+            }
+            (HomePopup::Plan(popup), Action::PrevItem) => popup.prev_item(),
+            (HomePopup::Plan(popup), Action::NextItem) => popup.next_item(),
+            (HomePopup::Plan(popup), Action::ConfirmPopup) => {
+                let plan = popup.plan().clone();
+                let connection = popup.connection().clone();
+                self.close_popup()?;
+                if self.profile.read_only {
+                    self.notify_read_only();
+                } else {
+                    self.apply_data_hub(plan, &connection);
+                }
+            }
+            (_, action) => return Ok(Some(action)),
+        }
+        Ok(None)
+    }
+
+    /// Returns the tab that started the task the action is the result of,
+    /// so results reach their tab even if another tab was selected in the meantime.
+    fn owning_tab(&self, action: &Action) -> usize {
+        let item_name = match action {
+            Action::ItemDeleted { item_name, .. }
+            | Action::ItemsLoadingFinished { item_name, .. }
+            | Action::ItemCreated { item_name, .. }
+            | Action::ItemUpdated { item_name, .. }
+            | Action::ReferencingPoliciesLoaded { item_name, .. }
+            | Action::DownloadProgress { item_name, .. }
+            | Action::DownloadFinished { item_name, .. } => item_name.as_str(),
+            Action::ClientDetailsLoadingProgress { .. }
+            | Action::ClientDetailsLoadingFinished { .. }
+            | Action::ClientSubscriptionsLoadingFinished { .. }
+            | Action::ClientStateLoadingFinished { .. }
+            | Action::ClientsInStateFound { .. }
+            | Action::ClientDisconnected(_)
+            | Action::ClientSessionInvalidated(_)
+            | Action::ClientBulkActionFinished { .. } => clients::ITEM_NAME,
+            Action::TraceRecordingStopped(_) => trace_recordings::ITEM_NAME,
+            Action::BackupRestoreStarted(_) | Action::BackupRestoreFinished(_) => {
+                backups::ITEM_NAME
+            }
+            _ => return self.active_tab,
+        };
+        self.tabs
+            .iter()
+            .position(|tab| tab.get_item_name() == item_name)
+            .unwrap_or(self.active_tab)
+    }
+
+    /// Passes the action to a tab and sends the action the tab returns.
+    /// The mode is kept while a popup is open or the tab is not selected,
+    /// so the tab can not change the key bindings of what is shown.
+    fn update_tab(&mut self, tab: usize, action: Action) -> Result<()> {
+        let tab_action = if tab == self.active_tab && self.popup.is_none() {
+            self.tabs[tab].update(action)?
+        } else {
+            let mode = *self.mode.borrow();
+            let tab_action = self.tabs[tab].update(action)?;
+            self.mode.replace(mode);
+            tab_action
+        };
+        if let Some(tab_action) = tab_action {
+            self.action_tx.send(tab_action)?;
+        }
+        Ok(())
     }
 
     pub fn select_tab(&mut self, index: usize) {
         if index != self.active_tab && index < self.tabs.len() {
+            self.tabs[self.active_tab].deactivate().unwrap();
             self.active_tab = index;
             self.tabs[self.active_tab].activate().unwrap();
         }
@@ -98,6 +396,7 @@ impl Home {
 
     pub fn next_tab(&mut self) {
         if self.active_tab < self.tabs.len() - 1 {
+            self.tabs[self.active_tab].deactivate().unwrap();
             self.active_tab = self.active_tab + 1;
             self.tabs[self.active_tab].activate().unwrap();
         }
@@ -105,6 +404,7 @@ impl Home {
 
     pub fn prev_tab(&mut self) {
         if self.active_tab > 0 {
+            self.tabs[self.active_tab].deactivate().unwrap();
             self.active_tab = self.active_tab - 1;
             self.tabs[self.active_tab].activate().unwrap();
         }
@@ -130,6 +430,22 @@ impl Component for Home {
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        match &mut self.popup {
+            Some(HomePopup::Profiles(_)) => return Ok(None),
+            Some(HomePopup::ExportDataHub(popup) | HomePopup::PlanDataHub(popup)) => {
+                popup.handle_key_events(key);
+                return Ok(None);
+            }
+            Some(HomePopup::Plan(popup)) => {
+                match key.code {
+                    KeyCode::PageUp => popup.scroll_diff_up(),
+                    KeyCode::PageDown => popup.scroll_diff_down(),
+                    _ => {}
+                }
+                return Ok(None);
+            }
+            None => {}
+        }
         let tab_action = self.tabs[self.active_tab].handle_key_events(key)?;
         if tab_action.is_some() {
             self.action_tx.send(tab_action.unwrap())?;
@@ -148,13 +464,63 @@ impl Component for Home {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let Some(action) = self.update_popup(action)? else {
+            return Ok(None);
+        };
+
+        if self.profile.read_only && action.is_mutating() {
+            self.notify_read_only();
+            return Ok(None);
+        }
+
         match action {
+            Action::DataHubExported(result) => self.notify_data_hub_exported(result),
+            Action::DataHubApplied(result) => self.notify_data_hub_applied(result)?,
+            Action::SelectProfile => self.open_profile_popup(),
+            Action::ExportDataHub => self.open_export_popup(),
+            Action::PlanDataHub => self.open_plan_input_popup(),
+            Action::DataHubPlanned(std::result::Result::Ok(plan)) => self.open_plan_popup(plan),
+            Action::DataHubPlanned(Err(message)) => {
+                self.notifications.push(Notification::error(
+                    "Planning Data Hub failed".to_string(),
+                    message,
+                ));
+            }
+            Action::ApplyDataHub(plan) => self.apply_data_hub(plan, &self.connection),
             Action::SelectTab(tab) => self.select_tab(tab),
             Action::NextTab => self.next_tab(),
             Action::PrevTab => self.prev_tab(),
-            _ => {
-                self.tabs[self.active_tab].update(action).unwrap();
+            Action::ShowClientsInState { .. } => {
+                self.select_tab(CLIENTS_TAB);
+                self.update_tab(CLIENTS_TAB, action)?;
+            }
+            Action::ShowItem { item_type, .. } => {
+                let tab = match item_type {
+                    ItemType::Schema => SCHEMAS_TAB,
+                    ItemType::Script => SCRIPTS_TAB,
+                    ItemType::DataPolicy => DATA_POLICIES_TAB,
+                    ItemType::BehaviorPolicy => BEHAVIOR_POLICIES_TAB,
+                };
+                self.select_tab(tab);
+                self.update_tab(tab, action)?;
+            }
+            Action::StartDataHubTrialMode => {
+                let service = self.data_hub_service.clone();
+                let tx = self.action_tx.clone();
+                tokio::spawn(async move {
+                    let result = service.start_trial_mode().await;
+                    tx.send(Action::DataHubTrialModeStarted(result)).unwrap();
+                });
+            }
+            Action::DataHubTrialModeStarted(result) if result.is_ok() => {
+                for tab in DATA_HUB_TABS {
+                    self.update_tab(tab, Action::LoadAllItems)?;
+                }
             }
+            Action::Notify(_) => {
+                self.notifications.update(action)?;
+            }
+            _ => self.update_tab(self.owning_tab(&action), action)?,
         }
 
         Ok(None)
@@ -191,19 +557,39 @@ impl Component for Home {
             .map(|(index, tab)| format!(" {} [{}] ", tab.get_name().to_string(), index + 1))
             .collect();
         let header = Tabs::new(titles.to_vec())
-            .highlight_style(Style::default().bg(Color::Blue).not_dim().underlined())
+            .highlight_style(theme().tab_highlight)
             .style(Style::default().dim())
             .select(self.active_tab)
             .padding("", "")
             .divider("");
         f.render_widget(header, header_area);
+        let mut profile_title = format!(" {} ", self.profile.name);
+        if self.profile.read_only {
+            profile_title.push_str("[read-only] ");
+        }
         f.render_widget(
-            Block::default().borders(Borders::BOTTOM).dim(),
+            Block::default()
+                .borders(Borders::BOTTOM)
+                .title(
+                    block::Title::from(profile_title)
+                        .alignment(Alignment::Right)
+                        .position(block::Position::Bottom),
+                )
+                .dim(),
             header_ruler_area,
         );
 
         // Create Tab
         (&mut tabs[self.active_tab]).draw(f, tab_area)?;
+        self.notifications.draw(f, tab_area)?;
+        match &mut self.popup {
+            Some(HomePopup::Profiles(popup)) => popup.draw(f, tab_area)?,
+            Some(HomePopup::ExportDataHub(popup) | HomePopup::PlanDataHub(popup)) => {
+                popup.draw(f, tab_area)?
+            }
+            Some(HomePopup::Plan(popup)) => popup.draw(f, tab_area)?,
+            None => {}
+        }
 
         // Create Footer
         f.render_widget(Paragraph::new(key_bindings), footer_area);
@@ -212,14 +598,94 @@ impl Component for Home {
     }
 }
 
+/// Returns a sender for the tasks of a profile that forwards their actions until the token is cancelled.
+/// Afterwards the results of tasks that still run for the previous broker are discarded.
+fn forward_tab_actions(
+    action_tx: &UnboundedSender<Action>,
+    cancellation_token: CancellationToken,
+) -> UnboundedSender<Action> {
+    let (tab_tx, mut tab_rx) = mpsc::unbounded_channel();
+    let action_tx = action_tx.clone();
+    tokio::spawn(async move {
+        // Keeps receiving until all tasks of the tabs are done, so their sends do not fail
+        while let Some(action) = tab_rx.recv().await {
+            if !cancellation_token.is_cancelled() {
+                let _ = action_tx.send(action);
+            }
+        }
+    });
+    tab_tx
+}
+
+fn create_tabs(
+    action_tx: &UnboundedSender<Action>,
+    connection: &HivemqConnection,
+    client_details_concurrency: usize,
+    download_dir: &Path,
+    mode: &Rc<RefCell<Mode>>,
+) -> [Box<dyn TabComponent>; 8] {
+    let sqlite_pool = init_sqlite();
+    [
+        Box::new(Clients::new(
+            action_tx.clone(),
+            connection.clone(),
+            client_details_concurrency,
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(SchemasTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(ScriptsTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(DataPoliciesTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(BehaviorPoliciesTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(TraceRecordingsTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            download_dir.to_path_buf(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(BackupsTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            download_dir.to_path_buf(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+        Box::new(DiagnosticArchivesTab::new(
+            action_tx.clone(),
+            connection.clone(),
+            mode.clone(),
+            &sqlite_pool,
+        )),
+    ]
+}
+
 fn split_at_width(items: &Vec<String>, max_width: u16) -> Vec<Line> {
     let mut current_width: u16 = 0;
     let mut lines: Vec<Line> = Vec::new();
     let mut current_line: Vec<Span> = Vec::new();
     for item in items {
-        let span1 = Span::default()
-            .style(Style::default().bg(Color::Blue))
-            .content(item);
+        let span1 = Span::default().style(theme().key_hint).content(item);
         let span2 = Span::default().content(" ");
         let item_len = item.chars().count() + 1;
         if (current_width + item_len as u16 > max_width) && current_width != 0 {
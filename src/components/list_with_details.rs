@@ -10,7 +10,7 @@ use ratatui::text::Span;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     prelude::Stylize,
-    style::{Color, Modifier, Style, Styled},
+    style::{Style, Styled},
     widgets::{block::Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
 };
 use serde::de::DeserializeOwned;
@@ -23,10 +23,15 @@ use LoadingState::Loaded;
 
 use crate::action::ListWithDetailsAction;
 use crate::components::list_with_details::FocusMode::Editing;
-use crate::components::list_with_details::ListPopup::{DeletePopup, ErrorPopup};
+use crate::components::list_with_details::ListPopup::{
+    CascadeDeletePopup, DeletePopup, ErrorPopup,
+};
 use crate::components::popups;
 use crate::components::popups::filter_popup::Tab;
+use crate::components::references_pane::ReferencesPane;
+use crate::external_editor;
 use crate::repository::Repository;
+use crate::theme::theme;
 use crate::{
     action::{Action, Action::SelectedItem},
     components::{
@@ -51,6 +56,10 @@ pub struct Features {
 
     #[builder(setter(strip_bool))]
     updatable: bool,
+
+    /// The items belong to Data Hub, so the Data Hub trial mode can be started if loading them fails.
+    #[builder(setter(strip_bool))]
+    data_hub: bool,
 }
 
 #[derive(TypedBuilder)]
@@ -67,6 +76,15 @@ pub struct ListWithDetails<'a, T: Serialize + DeserializeOwned> {
     #[builder]
     repository: Arc<Repository<T>>,
 
+    /// Checks the text of the create and update editors, see [validate]
+    #[builder(default = validate::<T>)]
+    validation: fn(&str) -> serde_json::Result<()>,
+
+    /// Shows the references of the selected Data Hub item below its details.
+    /// Schemas and scripts that are still referenced are deleted after their policies.
+    #[builder(default, setter(strip_option))]
+    references_pane: Option<ReferencesPane>,
+
     #[builder]
     mode: Rc<RefCell<Mode>>,
 
@@ -95,6 +113,13 @@ pub struct ListWithDetails<'a, T: Serialize + DeserializeOwned> {
 
     #[builder(setter(skip), default)]
     popup: Option<ListPopup<'a>>,
+
+    #[builder(setter(skip), default)]
+    loading_progress: Option<(usize, usize)>,
+
+    /// The item that is selected as soon as the items are loaded
+    #[builder(setter(skip), default)]
+    pending_selection: Option<String>,
 }
 
 pub enum LoadingState<'a> {
@@ -122,6 +147,7 @@ pub enum FocusMode<'a> {
 
 impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
     pub fn reset(&mut self) {
+        self.loading_progress = None;
         self.loading_state = Loaded {
             items: IndexSet::new(),
             list: vec![],
@@ -131,6 +157,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
     }
 
     pub fn set_items(&mut self, new_items: Vec<String>, filter: Option<String>) {
+        self.loading_progress = None;
         self.loading_state = Loaded {
             items: IndexSet::new(),
             list: vec![],
@@ -145,6 +172,18 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         }
     }
 
+    /// Appends items that were loaded so far while the loading of all items is still in progress.
+    /// The progress is shown until the items are set or the list is reset.
+    pub fn loading_progress(&mut self, new_items: Vec<String>, fetched: usize, total: usize) {
+        if let Loading = self.loading_state {
+            self.set_items(vec![], None);
+        }
+        for item in new_items {
+            self.put(item);
+        }
+        self.loading_progress = Some((fetched, total));
+    }
+
     pub fn put(&mut self, key: String) {
         if let Loaded { items, list, .. } = &mut self.loading_state {
             let old_value = items.insert(key.to_owned());
@@ -226,6 +265,39 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         }
     }
 
+    /// Returns the key of the selected item, also while the item is inspected in the editor.
+    pub fn get_selected_key(&self) -> Option<&String> {
+        let Loaded {
+            items, focus_mode, ..
+        } = &self.loading_state
+        else {
+            return None;
+        };
+
+        let list_state = match focus_mode {
+            FocusMode::Scrolling(list_state) => list_state,
+            FocusMode::Editing { list_state, .. } => list_state,
+            FocusMode::DetailsError { .. } => return None,
+        };
+
+        items.get_index(list_state.selected()?)
+    }
+
+    /// Returns the keys of all listed items, i.e. only the matching items if a filter is applied.
+    pub fn get_item_keys(&self) -> Vec<String> {
+        match &self.loading_state {
+            Loaded { items, .. } => items.iter().cloned().collect(),
+            _ => vec![],
+        }
+    }
+
+    pub fn get_filter(&self) -> Option<&String> {
+        match &self.loading_state {
+            Loaded { filter, .. } => filter.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn list_error(&mut self, msg: &str) {
         self.reset();
         self.loading_state = LoadingError(msg.to_owned());
@@ -247,6 +319,19 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         }
     }
 
+    /// Selects the item if it is in the list.
+    /// Otherwise it is selected after the items were loaded the next time and false is returned.
+    pub fn show_item(&mut self, item_key: &str) -> bool {
+        if let Loaded { items, .. } = &self.loading_state {
+            if items.iter().any(|key| key == item_key) {
+                self.select_item(item_key.to_string());
+                return true;
+            }
+        }
+        self.pending_selection = Some(item_key.to_string());
+        false
+    }
+
     pub fn set_scrolling_mode(&mut self) {
         if let Loaded { focus_mode, .. } = &mut self.loading_state {
             match focus_mode {
@@ -262,7 +347,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         }
     }
 
-    fn loading(&mut self) {
+    pub fn loading(&mut self) {
         self.reset();
         self.loading_state = Loading;
     }
@@ -358,7 +443,8 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         let item = serde_json::to_string_pretty(&item).unwrap();
         if self.features.updatable {
             let update_editor =
-                Editor::writeable_with_text(format!("Update {}", self.item_name), item.to_owned());
+                Editor::writeable_with_text(format!("Update {}", self.item_name), item.to_owned())
+                    .with_validation(self.validation);
             *focus_mode = Editing {
                 list_state: list_state.clone(),
                 editor: update_editor,
@@ -374,7 +460,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
                 list_state: list_state.clone(),
                 editor,
             };
-            *self.mode.borrow_mut() = Mode::EditorReadOnly;
+            *self.mode.borrow_mut() = Mode::DetailsEditor;
         }
     }
     fn confirm_popup(&mut self) -> Option<Action> {
@@ -386,6 +472,13 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
             DeletePopup { item_id, .. } => {
                 Some(Action::LWD(ListWithDetailsAction::Delete(item_id.clone())))
             }
+            CascadeDeletePopup { .. } => {
+                let Some(CascadeDeletePopup { popup }) = self.popup.take() else {
+                    return None;
+                };
+                *self.mode.borrow_mut() = self.base_mode;
+                Some(Action::ApplyDataHub(popup.into_plan()))
+            }
             ErrorPopup { .. } => None,
             FilterPopup { popup, .. } => {
                 let (items, filter) = match popup.get_selected_tab() {
@@ -464,6 +557,9 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
             ..
         } = &self.loading_state
         {
+            if !editor.is_valid() {
+                return None;
+            }
             Some(Action::LWD(ListWithDetailsAction::Update(
                 editor.get_text(),
             )))
@@ -474,6 +570,9 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
 
     fn create_item(&mut self) -> Option<Action> {
         if let Some(editor) = &mut self.new_item_editor {
+            if !editor.is_valid() {
+                return None;
+            }
             Some(Action::LWD(ListWithDetailsAction::Create(
                 editor.get_text(),
             )))
@@ -482,12 +581,61 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         }
     }
 
+    /// Returns the editor of the new item or of the item being updated.
+    fn writeable_editor(&mut self) -> Option<&mut Editor<'a>> {
+        if let Some(editor) = &mut self.new_item_editor {
+            return Some(editor);
+        }
+        match &mut self.loading_state {
+            Loaded {
+                focus_mode: Editing { editor, .. },
+                ..
+            } if !editor.is_readonly() => Some(editor),
+            _ => None,
+        }
+    }
+
+    /// Returns the editor of the inspected item.
+    fn readonly_editor(&mut self) -> Option<&mut Editor<'a>> {
+        match &mut self.loading_state {
+            Loaded {
+                focus_mode: Editing { editor, .. },
+                ..
+            } if editor.is_readonly() => Some(editor),
+            _ => None,
+        }
+    }
+
+    fn handle_external_editor_closed(&mut self, result: Result<String, String>) -> Option<Action> {
+        let editor = self.writeable_editor()?;
+        match result {
+            Ok(text) => {
+                let text = external_editor::strip_error_comment(&text);
+                let changed = text != editor.get_text();
+                editor.set_text(text);
+                // Invalid changes are fixed in the external editor, which shows the error above the text
+                if changed {
+                    if let Some(error) = editor.error() {
+                        let text = external_editor::with_error_comment(text, error);
+                        return Some(Action::RunExternalEditor(text));
+                    }
+                }
+            }
+            Err(message) => self.error_popup(popups::error_popup::ErrorPopup {
+                title: "External editor failed".to_string(),
+                message,
+            }),
+        }
+        None
+    }
+
     fn enter_new_item_editor(&mut self) {
         if self.features.creatable {
             self.set_scrolling_mode();
-            self.new_item_editor = Some(Editor::writeable(
-                format!("New {}", self.item_name).to_owned(),
-            ));
+            self.new_item_editor = Some(
+                Editor::writeable(format!("New {}", self.item_name).to_owned())
+                    .with_validation(self.validation),
+            );
             *self.mode.borrow_mut() = Mode::CreateEditor;
         }
     }
@@ -510,22 +658,53 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         }
     }
 
-    fn popup_delete_confirmation(&mut self) {
-        if let Some((id, _)) = self.get_selected() {
-            if self.features.deletable {
-                let item_type = self.item_name.clone();
-                let item_id = id.clone();
-                let popup = popups::confirm_popup::ConfirmPopup {
-                    title: format!("Delete {} '{}'?", item_type, item_id).to_string(),
-                    message: format!(
-                        "Are you sure you want to delete the {} with id '{}'",
-                        item_type, item_id
-                    )
-                    .to_string(),
-                };
-                self.enter_popup(DeletePopup { popup, item_id })
-            }
+    fn popup_delete_confirmation(&mut self) -> Option<Action> {
+        if !self.features.deletable {
+            return None;
         }
+        let (id, _) = self.get_selected()?;
+        let item_id = id.clone();
+
+        if self
+            .references_pane
+            .as_ref()
+            .is_some_and(ReferencesPane::is_referenced_by_policies)
+        {
+            return Some(Action::LWD(ListWithDetailsAction::LoadReferencingPolicies(
+                item_id,
+            )));
+        }
+        self.popup_confirm_delete(item_id);
+        None
+    }
+
+    /// Asks to delete the referencing policies first, if there are any, or to delete the item.
+    fn handle_referencing_policies_loaded(&mut self, item_id: String, result: Result<(), String>) {
+        let Some(references_pane) = &mut self.references_pane else {
+            return;
+        };
+        references_pane.refresh();
+        match result.and_then(|_| references_pane.cascade_delete_popup(&item_id)) {
+            Ok(Some(popup)) => self.enter_popup(CascadeDeletePopup { popup }),
+            Ok(None) => self.popup_confirm_delete(item_id),
+            Err(message) => self.error_popup(popups::error_popup::ErrorPopup {
+                title: format!("Deleting {} '{item_id}' failed", self.item_name),
+                message: format!("The policies that reference it could not be checked: {message}"),
+            }),
+        }
+    }
+
+    fn popup_confirm_delete(&mut self, item_id: String) {
+        let item_type = self.item_name.clone();
+        let popup = popups::confirm_popup::ConfirmPopup {
+            title: format!("Delete {} '{}'?", item_type, item_id).to_string(),
+            message: format!(
+                "Are you sure you want to delete the {} with id '{}'",
+                item_type, item_id
+            )
+            .to_string(),
+        };
+        self.enter_popup(DeletePopup { popup, item_id })
     }
 
     pub fn popup_filter(&mut self) {
@@ -538,6 +717,9 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
             Ok(_) => {
                 let items = self.repository.find_all_ids().unwrap();
                 self.set_items(items, None);
+                if let Some(item_key) = self.pending_selection.take() {
+                    self.select_item(item_key);
+                }
             }
             Err(msg) => self.list_error(&msg),
         }
@@ -549,7 +731,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
 
     fn enter_popup(&mut self, popup: ListPopup<'a>) {
         *self.mode.borrow_mut() = match popup {
-            DeletePopup { .. } => Mode::ConfirmPopup,
+            DeletePopup { .. } | CascadeDeletePopup { .. } => Mode::ConfirmPopup,
             ErrorPopup { .. } => Mode::ErrorPopup,
             FilterPopup { .. } => Mode::FilterPopup,
         };
@@ -568,6 +750,27 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         f: &mut Frame<'_>,
         area: Rect,
         custom_component: Option<&mut dyn Component>,
+    ) -> Result<()> {
+        self.draw_layout(f, area, custom_component, &mut [])
+    }
+
+    /// Draws the list and the details while reserving the lower part of the details area
+    /// for additional panes side by side, e.g. for data that belongs to the selected item.
+    pub fn draw_with_details_panes(
+        &mut self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        details_panes: &mut [&mut dyn Component],
+    ) -> Result<()> {
+        self.draw_layout(f, area, None, details_panes)
+    }
+
+    fn draw_layout(
+        &mut self,
+        f: &mut Frame<'_>,
+        area: Rect,
+        custom_component: Option<&mut dyn Component>,
+        details_panes: &mut [&mut dyn Component],
     ) -> Result<()> {
         let layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -575,15 +778,44 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
             .split(area);
 
         let list_layout = layout[0];
-        let detail_layout = layout[1];
+        let panes_count = details_panes.len() + usize::from(self.references_pane.is_some());
+        let detail_layout = if panes_count == 0 {
+            layout[1]
+        } else {
+            let details_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(layout[1]);
+            let panes_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Ratio(1, panes_count as u32); panes_count])
+                .split(details_layout[1]);
+            for (details_pane, pane_layout) in details_panes.iter_mut().zip(panes_layout.iter()) {
+                details_pane.draw(f, *pane_layout)?;
+            }
+            let selected_key = self.get_selected_key().cloned();
+            if let Some(references_pane) = &mut self.references_pane {
+                references_pane.select_item(selected_key.as_ref());
+                references_pane.draw(f, panes_layout[panes_count - 1])?;
+            }
+            details_layout[0]
+        };
         let detail_title = self.item_name.clone();
         let list_title = self.list_title.clone();
 
         match &mut self.loading_state {
             LoadingError(msg) => {
-                let p = Paragraph::new(msg.clone())
+                let msg = if self.features.data_hub {
+                    format!(
+                        "{msg}\n\nUse 'Start Trial' of the key hints below \
+                        to start the Data Hub trial mode."
+                    )
+                } else {
+                    msg.clone()
+                };
+                let p = Paragraph::new(msg)
                     .wrap(Wrap { trim: true })
-                    .style(Style::default().fg(Color::Red))
+                    .style(theme().error)
                     .block(
                         Block::default()
                             .borders(Borders::ALL)
@@ -601,7 +833,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
             Loading => {
                 let b = Block::default()
                     .borders(Borders::ALL)
-                    .style(Style::default().fg(Color::Blue))
+                    .style(theme().accent)
                     .title(format!("Loading {list_title}..."));
                 f.render_widget(b, list_layout);
                 f.render_widget(
@@ -644,20 +876,22 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
                     list.len()
                 ));
                 title_spans.push(title);
+                if let Some((fetched, total)) = self.loading_progress {
+                    let progress_title = Span::default()
+                        .content(format!(" loading {fetched}/{total}"))
+                        .style(theme().accent);
+                    title_spans.push(progress_title);
+                }
                 if let Some(filter_str) = filter {
                     let filter_title = Span::default()
                         .content(format!(" filtered by '{}'", &filter_str))
-                        .style(Style::default().fg(Color::Blue));
+                        .style(theme().accent);
                     title_spans.push(filter_title);
                 }
 
                 let list_widget = List::new(list.clone())
                     .block(Block::default().borders(Borders::ALL).title(title_spans))
-                    .highlight_style(
-                        Style::default()
-                            .bg(Color::Blue)
-                            .add_modifier(Modifier::BOLD),
-                    )
+                    .highlight_style(theme().list_selection)
                     .set_style(list_style);
 
                 f.render_stateful_widget(list_widget, list_layout, &mut list_state.clone());
@@ -700,7 +934,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
                     FocusMode::DetailsError { title, message } => {
                         let p = Paragraph::new(message.clone())
                             .wrap(Wrap { trim: true })
-                            .style(Style::default().fg(Color::Red))
+                            .style(theme().error)
                             .block(
                                 Block::default()
                                     .borders(Borders::ALL)
@@ -715,6 +949,7 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
         if let Some(popup) = &mut self.popup {
             let popup: &mut dyn popups::Popup = match popup {
                 DeletePopup { popup, .. } => popup,
+                CascadeDeletePopup { popup } => popup,
                 ErrorPopup { popup, .. } => popup,
                 FilterPopup { popup } => popup,
             };
@@ -727,11 +962,22 @@ impl<'a, T: Serialize + DeserializeOwned> ListWithDetails<'a, T> {
 
 impl<T: Serialize + DeserializeOwned> Component for ListWithDetails<'_, T> {
     fn activate(&mut self) -> Result<()> {
+        if let Some(references_pane) = &mut self.references_pane {
+            references_pane.refresh();
+        }
         *self.mode.borrow_mut() = self.base_mode;
         Ok(())
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self
+            .references_pane
+            .as_ref()
+            .is_some_and(ReferencesPane::is_focused)
+        {
+            return Ok(None);
+        }
+
         if let Some(ListPopup::FilterPopup { popup }) = &mut self.popup {
             return popup.handle_key_events(key);
         }
@@ -760,6 +1006,21 @@ impl<T: Serialize + DeserializeOwned> Component for ListWithDetails<'_, T> {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Some(references_pane) = &mut self.references_pane {
+            if references_pane.is_focused()
+                && matches!(
+                    action,
+                    Action::PrevItem | Action::NextItem | Action::ClosePopup | Action::ConfirmPopup
+                )
+            {
+                let action = references_pane.update(action)?;
+                if !references_pane.is_focused() {
+                    *self.mode.borrow_mut() = self.base_mode;
+                }
+                return Ok(action);
+            }
+        }
+
         match action {
             Action::LoadAllItems => {
                 self.loading();
@@ -796,7 +1057,14 @@ impl<T: Serialize + DeserializeOwned> Component for ListWithDetails<'_, T> {
                 }
             }
             Action::Delete => {
-                self.popup_delete_confirmation();
+                return Ok(self.popup_delete_confirmation());
+            }
+            Action::ReferencingPoliciesLoaded {
+                item_name,
+                item_id,
+                result,
+            } if item_name == self.item_name => {
+                self.handle_referencing_policies_loaded(item_id, result);
             }
             Action::Filter => {
                 self.popup_filter();
@@ -810,6 +1078,14 @@ impl<T: Serialize + DeserializeOwned> Component for ListWithDetails<'_, T> {
             Action::CreateItem => {
                 return Ok(self.create_item());
             }
+            Action::OpenExternalEditor => {
+                if let Some(editor) = self.writeable_editor() {
+                    return Ok(Some(Action::RunExternalEditor(editor.get_text())));
+                }
+            }
+            Action::ExternalEditorClosed(result) => {
+                return Ok(self.handle_external_editor_closed(result));
+            }
             Action::UpdateItem => {
                 return Ok(self.update_item());
             }
@@ -827,12 +1103,51 @@ impl<T: Serialize + DeserializeOwned> Component for ListWithDetails<'_, T> {
             Action::Copy => {
                 self.copy_json();
             }
+            Action::Search => {
+                if let Some(editor) = self.readonly_editor() {
+                    editor.start_search();
+                    *self.mode.borrow_mut() = Mode::SearchInput;
+                }
+            }
+            Action::SearchNext => {
+                if let Some(editor) = self.readonly_editor() {
+                    editor.search_next();
+                }
+            }
+            Action::SearchPrev => {
+                if let Some(editor) = self.readonly_editor() {
+                    editor.search_prev();
+                }
+            }
+            Action::ClosePopup | Action::ConfirmPopup
+                if self
+                    .readonly_editor()
+                    .is_some_and(|editor| editor.is_searching()) =>
+            {
+                if let Some(editor) = self.readonly_editor() {
+                    editor.end_search(action == Action::ConfirmPopup);
+                }
+                *self.mode.borrow_mut() = Mode::DetailsEditor;
+            }
             Action::ClosePopup => {
                 self.exit_popup();
             }
             Action::ConfirmPopup => {
                 return Ok(self.confirm_popup());
             }
+            Action::GoToReference => {
+                if let Some(references_pane) = &mut self.references_pane {
+                    if references_pane.focus() {
+                        *self.mode.borrow_mut() = Mode::ReferencesPane;
+                    }
+                }
+            }
+            Action::DataHubTrialModeStarted(Err(message)) if self.features.data_hub => {
+                self.error_popup(popups::error_popup::ErrorPopup {
+                    title: "Starting Data Hub trial mode failed".to_string(),
+                    message,
+                });
+            }
             _ => {}
         }
 
@@ -844,11 +1159,20 @@ impl<T: Serialize + DeserializeOwned> Component for ListWithDetails<'_, T> {
     }
 }
 
+/// Checks that the text of an editor parses as the type the tab sends to the broker,
+/// so invalid items are not submitted.
+pub fn validate<T: DeserializeOwned>(text: &str) -> serde_json::Result<()> {
+    serde_json::from_str::<T>(text).map(|_| ())
+}
+
 enum ListPopup<'a> {
     DeletePopup {
         popup: popups::confirm_popup::ConfirmPopup,
         item_id: String,
     },
+    CascadeDeletePopup {
+        popup: popups::cascade_delete_popup::CascadeDeletePopup,
+    },
     ErrorPopup {
         popup: popups::error_popup::ErrorPopup,
     },
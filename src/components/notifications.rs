@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use serde::Serialize;
+
+use crate::action::Action;
+use crate::components::Component;
+use crate::theme::theme;
+use crate::tui::Frame;
+
+/// How long a notification is shown.
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
+/// The maximum number of notifications that are shown at the same time.
+const MAX_NOTIFICATIONS: usize = 3;
+const NOTIFICATION_WIDTH: u16 = 50;
+const NOTIFICATION_HEIGHT: u16 = 4;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum NotificationLevel {
+    Info,
+    Error,
+}
+
+/// A short message about something that happened in the background, e.g. a finished backup.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+    pub level: NotificationLevel,
+}
+
+impl Notification {
+    pub fn info(title: String, message: String) -> Self {
+        Notification {
+            title,
+            message,
+            level: NotificationLevel::Info,
+        }
+    }
+
+    pub fn error(title: String, message: String) -> Self {
+        Notification {
+            title,
+            message,
+            level: NotificationLevel::Error,
+        }
+    }
+}
+
+/// Shows the latest notifications in the top right corner until they expire.
+#[derive(Default)]
+pub struct Notifications {
+    notifications: VecDeque<(Notification, Instant)>,
+}
+
+impl Notifications {
+    pub fn push(&mut self, notification: Notification) {
+        self.notifications.push_back((notification, Instant::now()));
+        while self.notifications.len() > MAX_NOTIFICATIONS {
+            self.notifications.pop_front();
+        }
+    }
+}
+
+impl Component for Notifications {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if let Action::Notify(notification) = action {
+            self.push(notification);
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        self.notifications
+            .retain(|(_, created)| created.elapsed() < NOTIFICATION_DURATION);
+
+        let width = NOTIFICATION_WIDTH.min(area.width);
+        let mut y = area.y;
+        for (notification, _) in self.notifications.iter().rev() {
+            if y + NOTIFICATION_HEIGHT > area.bottom() {
+                break;
+            }
+            let notification_area = Rect::new(area.right() - width, y, width, NOTIFICATION_HEIGHT);
+            let style = match notification.level {
+                NotificationLevel::Info => theme().success,
+                NotificationLevel::Error => theme().error,
+            };
+            let paragraph = Paragraph::new(notification.message.clone())
+                .wrap(Wrap { trim: true })
+                .style(style)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(notification.title.clone()),
+                );
+            f.render_widget(Clear, notification_area);
+            f.render_widget(paragraph, notification_area);
+            y += NOTIFICATION_HEIGHT;
+        }
+
+        Ok(())
+    }
+}
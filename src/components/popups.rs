@@ -2,15 +2,22 @@ use color_eyre::eyre::{Ok, Result};
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment::Center, Constraint, Direction, Layout, Rect},
-    style::{Color, Style, Stylize},
+    style::{Style, Stylize},
     widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
 };
 
 use crate::tui::Frame;
 
+pub mod cascade_delete_popup;
 pub mod confirm_popup;
 pub mod error_popup;
 pub mod filter_popup;
+pub mod info_popup;
+pub mod input_popup;
+pub mod plan_popup;
+pub mod profile_popup;
+pub mod progress_popup;
+pub mod typed_confirm_popup;
 
 pub trait Popup {
     fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> Result<()>;
@@ -35,14 +42,14 @@ pub fn draw_default_popup(
     popup_area: Rect,
     title: String,
     message: String,
-    color: Color,
+    style: Style,
     footer: String,
 ) {
     let block = Block::default()
         .title(title)
         .title_alignment(Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(color));
+        .border_style(style);
 
     let inner = block.inner(popup_area);
     let layout = Layout::default()
@@ -54,11 +61,9 @@ pub fn draw_default_popup(
 
     let message = Paragraph::new(message)
         .wrap(Wrap { trim: true })
-        .style(Style::default().fg(color));
+        .style(style);
 
-    let footer = Paragraph::new(footer)
-        .alignment(Center)
-        .style(Style::default().fg(color));
+    let footer = Paragraph::new(footer).alignment(Center).style(style);
 
     f.render_widget(block, popup_area);
     f.render_widget(message, popup_body);
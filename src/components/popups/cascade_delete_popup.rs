@@ -0,0 +1,61 @@
+use ratatui::layout::Rect;
+
+use crate::components::popups::{draw_default_popup, Popup};
+use crate::references::Reference;
+use crate::services::data_hub_sync_service::{ItemType, Plan};
+use crate::theme::theme;
+use crate::tui::Frame;
+
+/// Confirms the deletion of a schema or script that is still referenced by policies.
+/// Confirming deletes the policies first and then the item.
+pub struct CascadeDeletePopup {
+    item_type: ItemType,
+    id: String,
+    plan: Plan,
+}
+
+impl CascadeDeletePopup {
+    /// Returns None if no policy references the item, so it can be deleted directly.
+    pub fn new(item_type: ItemType, id: &str, references: &[Reference]) -> Option<Self> {
+        let plan = Plan::delete_cascade(item_type, id, references);
+        if plan.changes.len() < 2 {
+            return None;
+        }
+        Some(CascadeDeletePopup {
+            item_type,
+            id: id.to_string(),
+            plan,
+        })
+    }
+
+    pub fn into_plan(self) -> Plan {
+        self.plan
+    }
+}
+
+impl Popup for CascadeDeletePopup {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let (policies, _) = self.plan.changes.split_at(self.plan.changes.len() - 1);
+        let policies: Vec<String> = policies
+            .iter()
+            .map(|planned| format!("- {} '{}'", planned.item_type, planned.id))
+            .collect();
+        let message = format!(
+            "The {} '{}' is still used by:\n{}\n\n\
+            Confirm to delete these policies in this order and then the {}.",
+            self.item_type,
+            self.id,
+            policies.join("\n"),
+            self.item_type
+        );
+        draw_default_popup(
+            f,
+            popup_area,
+            format!("Delete {} '{}' and its policies?", self.item_type, self.id),
+            message,
+            theme().error,
+            "[Esc] Cancel  [Enter] Delete in cascade".to_string(),
+        );
+        color_eyre::eyre::Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use crate::components::popups::{draw_default_popup, Popup};
+use crate::theme::theme;
 use ratatui::layout::Rect;
-use ratatui::prelude::Color;
 
 pub struct ErrorPopup {
     pub title: String,
@@ -18,7 +18,7 @@ impl Popup for ErrorPopup {
             popup_area,
             self.title.clone(),
             self.message.clone(),
-            Color::Red,
+            theme().error,
             "[Esc] Close".to_string(),
         );
         color_eyre::eyre::Ok(())
@@ -1,12 +1,13 @@
 use crate::action::Action;
 use crate::components::popups::filter_popup::Tab::{JsonPathSearch, KeywordSearch};
 use crate::components::popups::Popup;
+use crate::theme::theme;
 use crate::tui::Frame;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 use ratatui::layout::Alignment::Center;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::prelude::{Color, Style, Stylize};
+use ratatui::prelude::{Style, Stylize};
 
 use ratatui::widgets::{Block, Borders, Paragraph, Tabs};
 use std::ops::Not;
@@ -167,13 +168,13 @@ impl<'a> Popup for FilterPopup<'a> {
             .title(self.title)
             .title_alignment(Center)
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Blue));
+            .border_style(theme().accent);
 
         let inner = block.inner(popup_area);
 
         let tab_names = self.tab_names.clone();
         let tabs = Tabs::new(tab_names)
-            .highlight_style(Style::default().bg(Color::Blue).not_dim().underlined())
+            .highlight_style(theme().tab_highlight)
             .style(Style::default().dim())
             .select(self.selected)
             .padding("", "");
@@ -197,7 +198,7 @@ impl<'a> Popup for FilterPopup<'a> {
 
         let footer = Paragraph::new("[Tab] Select Search  [Esc] Close  [Enter] Filter")
             .alignment(Center)
-            .style(Style::default().fg(Color::Blue));
+            .style(theme().accent);
 
         f.render_widget(block, popup_area);
 
@@ -0,0 +1,26 @@
+use crate::components::popups::{draw_default_popup, Popup};
+use crate::theme::theme;
+use ratatui::layout::Rect;
+
+pub struct InfoPopup {
+    pub title: String,
+    pub message: String,
+}
+
+impl Popup for InfoPopup {
+    fn draw_popup(
+        &mut self,
+        f: &mut crate::tui::Frame<'_>,
+        popup_area: Rect,
+    ) -> color_eyre::Result<()> {
+        draw_default_popup(
+            f,
+            popup_area,
+            self.title.clone(),
+            self.message.clone(),
+            theme().success,
+            "[Esc] Close".to_string(),
+        );
+        color_eyre::eyre::Ok(())
+    }
+}
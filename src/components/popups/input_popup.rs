@@ -0,0 +1,74 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Alignment::Center;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Style;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use tui_textarea::TextArea;
+
+use crate::components::popups::Popup;
+use crate::theme::theme;
+use crate::tui::Frame;
+
+pub struct InputPopup<'a> {
+    title: String,
+    message: String,
+    text_area: TextArea<'a>,
+}
+
+impl<'a> InputPopup<'a> {
+    pub fn new(title: String, message: String, placeholder: &str) -> Self {
+        let mut text_area = TextArea::default();
+        text_area.set_cursor_line_style(Style::default());
+        text_area.set_placeholder_text(placeholder);
+        text_area.set_block(Block::default().borders(Borders::ALL));
+
+        InputPopup {
+            title,
+            message,
+            text_area,
+        }
+    }
+
+    pub fn get_input(&self) -> String {
+        self.text_area.lines()[0].trim().to_string()
+    }
+
+    pub fn handle_key_events(&mut self, key: KeyEvent) {
+        if key.code != KeyCode::Enter {
+            self.text_area.input(key);
+        }
+    }
+}
+
+impl Popup for InputPopup<'_> {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let block = Block::default()
+            .title(self.title.clone())
+            .title_alignment(Center)
+            .borders(Borders::ALL)
+            .border_style(theme().accent);
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let layout = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .horizontal_margin(1)
+        .split(inner);
+
+        let message = Paragraph::new(self.message.clone())
+            .wrap(Wrap { trim: true })
+            .style(theme().accent);
+        f.render_widget(message, layout[0]);
+        f.render_widget(self.text_area.widget(), layout[1]);
+
+        let footer = Paragraph::new("[Esc] Close  [Enter] Confirm")
+            .alignment(Center)
+            .style(theme().accent);
+        f.render_widget(footer, layout[2]);
+
+        color_eyre::eyre::Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use ratatui::layout::Alignment::Center;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use similar::{ChangeTag, TextDiff};
+
+use crate::components::popups::Popup;
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::services::data_hub_sync_service::{Change, Plan, PlannedChange};
+use crate::theme::theme;
+use crate::tui::Frame;
+
+/// Lines scrolled by PageUp and PageDown in the diff
+const DIFF_PAGE: u16 = 10;
+
+/// Shows the changes of a Data Hub plan and the diff of the selected change.
+pub struct PlanPopup {
+    plan: Plan,
+    /// The broker the plan was made for, which it must be applied to
+    connection: HivemqConnection,
+    list_state: ListState,
+    diff_scroll: u16,
+}
+
+impl PlanPopup {
+    pub fn new(plan: Plan, connection: HivemqConnection) -> Self {
+        let selected = if plan.is_empty() { None } else { Some(0) };
+        PlanPopup {
+            plan,
+            connection,
+            list_state: ListState::default().with_selected(selected),
+            diff_scroll: 0,
+        }
+    }
+
+    pub fn next_item(&mut self) {
+        if let Some(selected) = self.list_state.selected() {
+            if selected + 1 < self.plan.changes.len() {
+                self.list_state.select(Some(selected + 1));
+                self.diff_scroll = 0;
+            }
+        }
+    }
+
+    pub fn prev_item(&mut self) {
+        if let Some(selected) = self.list_state.selected() {
+            self.list_state.select(Some(selected.saturating_sub(1)));
+            self.diff_scroll = 0;
+        }
+    }
+
+    pub fn scroll_diff_up(&mut self) {
+        self.diff_scroll = self.diff_scroll.saturating_sub(DIFF_PAGE);
+    }
+
+    pub fn scroll_diff_down(&mut self) {
+        let lines = self
+            .list_state
+            .selected()
+            .and_then(|selected| self.plan.changes.get(selected))
+            .map(|planned| diff_lines(planned).len())
+            .unwrap_or_default();
+        let max_scroll = u16::try_from(lines.saturating_sub(1)).unwrap_or(u16::MAX);
+        self.diff_scroll = self.diff_scroll.saturating_add(DIFF_PAGE).min(max_scroll);
+    }
+
+    pub fn plan(&self) -> &Plan {
+        &self.plan
+    }
+
+    pub fn connection(&self) -> &HivemqConnection {
+        &self.connection
+    }
+}
+
+impl Popup for PlanPopup {
+    fn percent_y(&self) -> u16 {
+        80
+    }
+
+    fn percent_x(&self) -> u16 {
+        80
+    }
+
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let block = Block::default()
+            .title(format!("Data Hub Plan: {}", self.plan))
+            .title_alignment(Center)
+            .borders(Borders::ALL)
+            .border_style(theme().accent);
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let layout = Layout::vertical([Constraint::Percentage(100), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+        let content = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+            .split(layout[0]);
+
+        let items: Vec<ListItem> = self
+            .plan
+            .changes
+            .iter()
+            .map(|planned| {
+                ListItem::new(format!(
+                    "{} {} '{}'",
+                    planned.change, planned.item_type, planned.id
+                ))
+                .style(change_style(planned.change))
+            })
+            .collect();
+        let list = List::new(items).highlight_style(theme().list_selection);
+        f.render_stateful_widget(list, content[0], &mut self.list_state);
+
+        let diff = self
+            .list_state
+            .selected()
+            .and_then(|selected| self.plan.changes.get(selected))
+            .map(diff_lines)
+            .unwrap_or_default();
+        let diff = Paragraph::new(diff)
+            .scroll((self.diff_scroll, 0))
+            .block(Block::default().borders(Borders::LEFT));
+        f.render_widget(diff, content[1]);
+
+        let footer = Paragraph::new("[Esc] Close  [PgUp/PgDn] Scroll diff  [Enter] Apply")
+            .alignment(Center)
+            .style(theme().accent);
+        f.render_widget(footer, layout[1]);
+
+        color_eyre::eyre::Ok(())
+    }
+}
+
+fn change_style(change: Change) -> Style {
+    match change {
+        Change::Create => theme().success,
+        Change::Update => theme().accent,
+        Change::Delete => theme().error,
+    }
+}
+
+fn diff_lines(planned: &PlannedChange) -> Vec<Line<'static>> {
+    let current = planned.current.as_deref().unwrap_or_default();
+    let desired = planned.desired.as_deref().unwrap_or_default();
+    TextDiff::from_lines(current, desired)
+        .iter_all_changes()
+        .map(|change| {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ("-", theme().error),
+                ChangeTag::Insert => ("+", theme().success),
+                ChangeTag::Equal => (" ", Style::default()),
+            };
+            let line = change.to_string_lossy();
+            Line::from(Span::styled(
+                format!("{sign} {}", line.trim_end_matches('\n')),
+                style,
+            ))
+        })
+        .collect()
+}
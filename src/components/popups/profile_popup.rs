@@ -0,0 +1,98 @@
+use ratatui::layout::Alignment::Center;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::widgets::{Block, Borders, List, ListState, Paragraph, Wrap};
+
+use crate::components::popups::Popup;
+use crate::config::ProfileConfig;
+use crate::theme::theme;
+use crate::tui::Frame;
+
+/// Lets the user pick one of the configured connection profiles.
+pub struct ProfilePopup {
+    profiles: Vec<ProfileConfig>,
+    active_profile: String,
+    list_state: ListState,
+}
+
+impl ProfilePopup {
+    pub fn new(profiles: Vec<ProfileConfig>, active_profile: String) -> Self {
+        let selected = profiles
+            .iter()
+            .position(|profile| profile.name == active_profile)
+            .or(if profiles.is_empty() { None } else { Some(0) });
+        ProfilePopup {
+            profiles,
+            active_profile,
+            list_state: ListState::default().with_selected(selected),
+        }
+    }
+
+    pub fn next_item(&mut self) {
+        if let Some(selected) = self.list_state.selected() {
+            if selected + 1 < self.profiles.len() {
+                self.list_state.select(Some(selected + 1));
+            }
+        }
+    }
+
+    pub fn prev_item(&mut self) {
+        if let Some(selected) = self.list_state.selected() {
+            self.list_state.select(Some(selected.saturating_sub(1)));
+        }
+    }
+
+    pub fn get_selected(&self) -> Option<&ProfileConfig> {
+        self.list_state
+            .selected()
+            .and_then(|selected| self.profiles.get(selected))
+    }
+}
+
+impl Popup for ProfilePopup {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let block = Block::default()
+            .title("Connection Profiles")
+            .title_alignment(Center)
+            .borders(Borders::ALL)
+            .border_style(theme().accent);
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let layout = Layout::vertical([Constraint::Percentage(100), Constraint::Length(1)])
+            .horizontal_margin(1)
+            .split(inner);
+
+        if self.profiles.is_empty() {
+            let message = Paragraph::new(
+                "No profiles are configured. Add profiles to the 'profiles' list of the config file.",
+            )
+            .wrap(Wrap { trim: true })
+            .style(theme().accent);
+            f.render_widget(message, layout[0]);
+        } else {
+            let items: Vec<String> = self
+                .profiles
+                .iter()
+                .map(|profile| {
+                    let mut item = format!("{} ({})", profile.name, profile.host);
+                    if profile.read_only {
+                        item.push_str(" [read-only]");
+                    }
+                    if profile.name == self.active_profile {
+                        item.push_str(" *");
+                    }
+                    item
+                })
+                .collect();
+            let list = List::new(items).highlight_style(theme().list_selection);
+            f.render_stateful_widget(list, layout[0], &mut self.list_state);
+        }
+
+        let footer = Paragraph::new("[Esc] Close  [Enter] Switch")
+            .alignment(Center)
+            .style(theme().accent);
+        f.render_widget(footer, layout[1]);
+
+        color_eyre::eyre::Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use ratatui::layout::Alignment::Center;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Wrap};
+
+use crate::components::popups::Popup;
+use crate::theme::theme;
+use crate::tui::Frame;
+
+/// Shows the progress of a long running transfer, e.g. a download.
+pub struct ProgressPopup {
+    title: String,
+    message: String,
+    transferred: u64,
+    total: Option<u64>,
+}
+
+impl ProgressPopup {
+    pub fn new(title: String, message: String) -> Self {
+        ProgressPopup {
+            title,
+            message,
+            transferred: 0,
+            total: None,
+        }
+    }
+
+    pub fn set_progress(&mut self, transferred: u64, total: Option<u64>) {
+        self.transferred = transferred;
+        self.total = total;
+    }
+}
+
+impl Popup for ProgressPopup {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let block = Block::default()
+            .title(self.title.clone())
+            .title_alignment(Center)
+            .borders(Borders::ALL)
+            .border_style(theme().accent);
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let layout = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .horizontal_margin(1)
+        .split(inner);
+
+        let message = Paragraph::new(self.message.clone())
+            .wrap(Wrap { trim: true })
+            .style(theme().accent);
+        f.render_widget(message, layout[0]);
+
+        let (ratio, label) = match self.total {
+            Some(total) if total > 0 => (
+                (self.transferred as f64 / total as f64).min(1.0),
+                format!("{} / {} bytes", self.transferred, total),
+            ),
+            _ => (0.0, format!("{} bytes", self.transferred)),
+        };
+        let gauge = Gauge::default()
+            .gauge_style(theme().accent)
+            .ratio(ratio)
+            .label(label);
+        f.render_widget(gauge, layout[1]);
+
+        let footer = Paragraph::new("[Esc] Close")
+            .alignment(Center)
+            .style(theme().accent);
+        f.render_widget(footer, layout[2]);
+
+        color_eyre::eyre::Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Alignment::Center;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Style;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use tui_textarea::TextArea;
+
+use crate::components::popups::Popup;
+use crate::theme::theme;
+use crate::tui::Frame;
+
+/// A confirmation that can only be confirmed after the expected text was typed,
+/// used for actions that can not be undone.
+pub struct TypedConfirmPopup<'a> {
+    title: String,
+    message: String,
+    expected: String,
+    text_area: TextArea<'a>,
+}
+
+impl<'a> TypedConfirmPopup<'a> {
+    pub fn new(title: String, message: String, expected: String) -> Self {
+        let mut text_area = TextArea::default();
+        text_area.set_cursor_line_style(Style::default());
+        text_area.set_placeholder_text(format!("Type '{expected}' to confirm"));
+        text_area.set_block(Block::default().borders(Borders::ALL).title("Confirmation"));
+
+        TypedConfirmPopup {
+            title,
+            message,
+            expected,
+            text_area,
+        }
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.text_area.lines()[0] == self.expected
+    }
+
+    pub fn handle_key_events(&mut self, key: KeyEvent) {
+        if key.code != KeyCode::Enter {
+            self.text_area.input(key);
+        }
+    }
+}
+
+impl Popup for TypedConfirmPopup<'_> {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let block = Block::default()
+            .title(self.title.clone())
+            .title_alignment(Center)
+            .borders(Borders::ALL)
+            .border_style(theme().error);
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let layout = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .horizontal_margin(1)
+        .split(inner);
+
+        let message = Paragraph::new(self.message.clone())
+            .wrap(Wrap { trim: true })
+            .style(theme().error);
+        f.render_widget(message, layout[0]);
+
+        let text_area_style = if self.is_confirmed() {
+            Style::default()
+        } else {
+            theme().error
+        };
+        self.text_area.set_style(text_area_style);
+        f.render_widget(self.text_area.widget(), layout[1]);
+
+        let footer = Paragraph::new("[Esc] Close  [Enter] Confirm")
+            .alignment(Center)
+            .style(theme().error);
+        f.render_widget(footer, layout[2]);
+
+        color_eyre::eyre::Ok(())
+    }
+
+    fn percent_y(&self) -> u16 {
+        50
+    }
+}
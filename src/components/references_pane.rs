@@ -0,0 +1,205 @@
+use color_eyre::eyre::Result;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use ratatui::{
+    layout::{Constraint, Rect},
+    prelude::Stylize,
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap},
+};
+
+use crate::{
+    action::Action,
+    components::{popups::cascade_delete_popup::CascadeDeletePopup, Component},
+    references::{DataHubReferences, Reference},
+    services::data_hub_sync_service::ItemType,
+    theme::theme,
+    tui::Frame,
+};
+
+/// Shows the schemas and scripts a policy references or the policies that reference a schema or script.
+/// The pane can be focused to select a reference and show it in its tab.
+pub struct ReferencesPane {
+    references: DataHubReferences,
+    item_type: ItemType,
+    item_id: Option<String>,
+    state: Result<Vec<Reference>, String>,
+    focus: Option<TableState>,
+}
+
+impl ReferencesPane {
+    pub fn new(sqlite_pool: &Pool<SqliteConnectionManager>, item_type: ItemType) -> Self {
+        ReferencesPane {
+            references: DataHubReferences::new(sqlite_pool),
+            item_type,
+            item_id: None,
+            state: Ok(Vec::new()),
+            focus: None,
+        }
+    }
+
+    /// Shows the references of the item if it is not shown already.
+    pub fn select_item(&mut self, item_id: Option<&String>) {
+        if self.item_id.as_ref() != item_id {
+            self.item_id = item_id.cloned();
+            self.refresh();
+        }
+    }
+
+    /// Reads the references of the shown item from the cache again.
+    pub fn refresh(&mut self) {
+        self.state = match &self.item_id {
+            None => Ok(Vec::new()),
+            Some(item_id) => self.references.find_by_item(self.item_type, item_id),
+        };
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// Focuses the pane to select one of the references.
+    /// Returns false if there are no references to select.
+    pub fn focus(&mut self) -> bool {
+        self.refresh();
+        match &self.state {
+            Ok(references) if !references.is_empty() => {
+                self.focus = Some(TableState::default().with_selected(Some(0)));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn unfocus(&mut self) {
+        self.focus = None;
+    }
+
+    /// Returns true if the items can be referenced by policies, i.e. for schemas and scripts.
+    pub fn is_referenced_by_policies(&self) -> bool {
+        matches!(self.item_type, ItemType::Schema | ItemType::Script)
+    }
+
+    /// Returns the popup that asks to delete the referencing policies first
+    /// if the schema or script is still referenced by the cached policies.
+    pub fn cascade_delete_popup(
+        &self,
+        item_id: &str,
+    ) -> Result<Option<CascadeDeletePopup>, String> {
+        if !self.is_referenced_by_policies() {
+            return Ok(None);
+        }
+        let references = self.references.find_by_item(self.item_type, item_id)?;
+        Ok(CascadeDeletePopup::new(
+            self.item_type,
+            item_id,
+            &references,
+        ))
+    }
+
+    fn get_selected_action(&self) -> Option<Action> {
+        let selected = self.focus.as_ref()?.selected()?;
+        let reference = self.state.as_ref().ok()?.get(selected)?;
+        let (item_type, id) = reference.target(self.item_type);
+        Some(Action::ShowItem {
+            item_type,
+            id: id.to_string(),
+        })
+    }
+
+    fn title(&self) -> &str {
+        match self.item_type {
+            ItemType::Schema | ItemType::Script => "Used by",
+            ItemType::DataPolicy | ItemType::BehaviorPolicy => "References",
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.state.as_ref().map_or(0, Vec::len)
+    }
+}
+
+impl Component for ReferencesPane {
+    /// Handles the actions while the pane is focused.
+    /// Confirming returns the action that shows the selected reference and unfocuses the pane.
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let len = self.len();
+        let Some(focus) = &mut self.focus else {
+            return Ok(None);
+        };
+        let selected = focus.selected().unwrap_or_default();
+        match action {
+            Action::PrevItem => focus.select(Some(selected.saturating_sub(1))),
+            Action::NextItem if selected + 1 < len => focus.select(Some(selected + 1)),
+            Action::ClosePopup => self.unfocus(),
+            Action::ConfirmPopup => {
+                let action = self.get_selected_action();
+                self.unfocus();
+                return Ok(action);
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let title = self.title();
+        match (&self.item_id, &self.state) {
+            (None, _) => {
+                f.render_widget(
+                    Block::default()
+                        .style(Style::default().dim())
+                        .borders(Borders::ALL)
+                        .title(title),
+                    area,
+                );
+            }
+            (Some(_), Err(message)) => {
+                let p = Paragraph::new(message.clone())
+                    .wrap(Wrap { trim: true })
+                    .style(theme().error)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!("Loading {title} failed")),
+                    );
+                f.render_widget(p, area);
+            }
+            (Some(_), Ok(references)) => {
+                let rows: Vec<Row> = references
+                    .iter()
+                    .map(|reference| {
+                        let (item_type, id) = reference.target(self.item_type);
+                        Row::new(vec![
+                            item_type.to_string(),
+                            id.to_string(),
+                            reference.version.clone(),
+                        ])
+                    })
+                    .collect();
+
+                let widths = [
+                    Constraint::Length(15),
+                    Constraint::Fill(1),
+                    Constraint::Length(7),
+                ];
+                let header = Row::new(vec!["Type", "ID", "Version"])
+                    .style(Style::default().add_modifier(Modifier::BOLD));
+                let mut block = Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{title} ({})", references.len()));
+                if self.focus.is_some() {
+                    block = block.border_style(theme().accent);
+                }
+                let table = Table::new(rows, widths)
+                    .header(header)
+                    .highlight_style(theme().list_selection)
+                    .block(block);
+                let mut table_state = self.focus.clone().unwrap_or_default();
+                f.render_stateful_widget(table, area, &mut table_state);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -4,10 +4,13 @@ pub mod backups;
 pub mod behavior_policies;
 pub mod clients;
 pub mod data_policies;
+pub mod diagnostic_archives;
 pub mod schemas;
 pub mod scripts;
 pub mod trace_recordings;
 
 pub trait TabComponent: Component {
     fn get_name(&self) -> &str;
+    /// Returns the name the tab tags the results of its tasks with, e.g. in `ItemsLoadingFinished`.
+    fn get_item_name(&self) -> &str;
 }
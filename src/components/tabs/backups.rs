@@ -1,16 +1,28 @@
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
+use hivemq_openapi::models::backup::State;
 use hivemq_openapi::models::Backup;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use ratatui::layout::Rect;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tokio_util::sync::{CancellationToken, DropGuard};
 
 use crate::components::list_with_details::Features;
+use crate::components::notifications::Notification;
+use crate::components::popups::error_popup::ErrorPopup;
+use crate::components::popups::info_popup::InfoPopup;
+use crate::components::popups::progress_popup::ProgressPopup;
+use crate::components::popups::typed_confirm_popup::TypedConfirmPopup;
+use crate::components::popups::Popup;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
 use crate::repository::Repository;
 use crate::services::backups_service::BackupService;
@@ -20,17 +32,36 @@ use crate::{
     tui::Frame,
 };
 
+/// The item name the Backups tab tags the results of its tasks with.
+pub const ITEM_NAME: &str = "Backup";
+/// The interval in which the state of a backup is polled while it is restored.
+const RESTORE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// The time after which waiting for a restore fails.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// The interval in which the backups are polled while a backup is in progress.
+const RUNNING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The number of failed polls in a row after which polling stops.
+const MAX_POLL_ERRORS: usize = 5;
+
 pub struct BackupsTab<'a> {
     action_tx: UnboundedSender<Action>,
+    mode: Rc<RefCell<Mode>>,
     list_with_details: ListWithDetails<'a, Backup>,
+    popup: Option<BackupsPopup<'a>>,
     service: Arc<BackupService>,
+    download_dir: PathBuf,
+    polling_task: Option<JoinHandle<()>>,
+    /// Cancels waiting for the running restore when the tab is dropped, e.g. on a profile switch.
+    /// Only one restore can run at a time.
+    restore_guard: Option<DropGuard>,
     item_name: &'static str,
 }
 
-impl BackupsTab<'_> {
+impl<'a> BackupsTab<'a> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
+        download_dir: PathBuf,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -42,38 +73,318 @@ impl BackupsTab<'_> {
         )
         .unwrap();
         let repository = Arc::new(repository);
-        let service = Arc::new(BackupService::new(repository.clone(), &hivemq_address));
-        let item_name = "Backup";
+        let service = Arc::new(BackupService::new(repository.clone(), &connection));
+        let item_name = ITEM_NAME;
         let list_with_details = ListWithDetails::<Backup>::builder()
             .list_title("Backups")
             .item_name(item_name)
-            .mode(mode)
+            .mode(mode.clone())
             .base_mode(Mode::BackupTab)
             .repository(repository.clone())
             .features(Features::builder().build())
             .build();
         BackupsTab {
             action_tx,
+            mode,
             list_with_details,
+            popup: None,
             service,
+            download_dir,
+            polling_task: None,
+            restore_guard: None,
             item_name,
         }
     }
+
+    fn popup_restore_confirmation(&mut self) {
+        let Some(backup_id) = self.list_with_details.get_selected_key().cloned() else {
+            return;
+        };
+        if self.restore_guard.is_some() {
+            self.list_with_details.error_popup(ErrorPopup {
+                title: "Restoring backup failed".to_string(),
+                message: "Another backup is being restored, wait until it is finished".to_string(),
+            });
+            return;
+        }
+        let popup = TypedConfirmPopup::new(
+            "Restore backup?".to_string(),
+            format!(
+                "Restoring '{backup_id}' replaces the current configuration of the broker \
+                with the configuration of the backup. This can not be undone."
+            ),
+            backup_id.clone(),
+        );
+        self.enter_popup(BackupsPopup::Restore {
+            backup_id,
+            popup: Box::new(popup),
+        });
+    }
+
+    fn enter_popup(&mut self, popup: BackupsPopup<'a>) {
+        *self.mode.borrow_mut() = match popup {
+            BackupsPopup::Restore { .. } => Mode::InputPopup,
+            BackupsPopup::Download(_) | BackupsPopup::Info(_) => Mode::ErrorPopup,
+        };
+        self.popup = Some(popup);
+    }
+
+    fn exit_popup(&mut self) {
+        if self.popup.take().is_some() {
+            self.list_with_details.activate().unwrap();
+        }
+    }
+
+    fn confirm_popup(&mut self) {
+        match self.popup.take() {
+            Some(BackupsPopup::Restore { backup_id, popup }) if popup.is_confirmed() => {
+                self.list_with_details.activate().unwrap();
+                self.restore_backup(backup_id);
+            }
+            popup => self.popup = popup,
+        }
+    }
+
+    fn restore_backup(&mut self, backup_id: String) {
+        let cancellation_token = CancellationToken::new();
+        self.restore_guard = Some(cancellation_token.clone().drop_guard());
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let result = service.restore_backup(&backup_id).await;
+            let started = result.is_ok();
+            tx.send(Action::BackupRestoreStarted(result)).unwrap();
+            if !started {
+                return;
+            }
+
+            let result = service
+                .wait_for_backup(
+                    &backup_id,
+                    RESTORE_POLL_INTERVAL,
+                    RESTORE_TIMEOUT,
+                    cancellation_token,
+                )
+                .await;
+            tx.send(Action::BackupRestoreFinished(result)).unwrap();
+        });
+    }
+
+    /// Keeps refreshing the backups in the background as long as any backup is in progress.
+    fn start_polling(&mut self) {
+        if self
+            .polling_task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            return;
+        }
+
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        let item_name = self.item_name.to_string();
+        self.polling_task = Some(tokio::spawn(async move {
+            let mut errors = 0;
+            while service.has_running_backups() {
+                tokio::time::sleep(RUNNING_POLL_INTERVAL).await;
+                let (finished, removed) = match service.refresh_running_backups().await {
+                    Ok(refreshed) => {
+                        errors = 0;
+                        refreshed
+                    }
+                    Err(message) => {
+                        log::warn!("Polling backups failed: {message}");
+                        errors += 1;
+                        if errors >= MAX_POLL_ERRORS {
+                            let _ = tx.send(Action::Notify(Notification::error(
+                                "Refreshing backups failed".to_string(),
+                                format!("Stopped refreshing the running backups: {message}"),
+                            )));
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                for backup in finished {
+                    let _ = tx.send(Action::Notify(backup_notification(&backup)));
+                }
+                for backup_id in removed {
+                    let _ = tx.send(Action::Notify(Notification::info(
+                        "Backup removed".to_string(),
+                        format!("Backup '{backup_id}' is not on the broker anymore"),
+                    )));
+                    let _ = tx.send(Action::ItemDeleted {
+                        item_name: item_name.clone(),
+                        result: Ok(backup_id),
+                    });
+                }
+            }
+        }));
+    }
+
+    fn download_backup(&mut self) {
+        let Some(backup_id) = self.list_with_details.get_selected_key().cloned() else {
+            return;
+        };
+        self.enter_popup(BackupsPopup::Download(ProgressPopup::new(
+            "Downloading backup".to_string(),
+            format!(
+                "Downloading '{backup_id}' to {}",
+                self.download_dir.display()
+            ),
+        )));
+
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        let directory = self.download_dir.clone();
+        let item_name = self.item_name.to_string();
+        tokio::spawn(async move {
+            let result = service
+                .download_backup(&backup_id, &directory, |downloaded, total| {
+                    let item_name = item_name.clone();
+                    tx.send(Action::DownloadProgress {
+                        item_name,
+                        downloaded,
+                        total,
+                    })
+                    .unwrap();
+                })
+                .await
+                .map(|path| path.display().to_string());
+            tx.send(Action::DownloadFinished { item_name, result })
+                .unwrap();
+        });
+    }
+
+    fn handle_download_finished(&mut self, result: Result<String, String>) {
+        if let Some(BackupsPopup::Download(_)) = self.popup {
+            self.exit_popup();
+        }
+        match result {
+            Ok(path) => self.enter_popup(BackupsPopup::Info(InfoPopup {
+                title: "Backup downloaded".to_string(),
+                message: format!("The backup was saved to {path}"),
+            })),
+            Err(message) => self.list_with_details.error_popup(ErrorPopup {
+                title: "Downloading backup failed".to_string(),
+                message,
+            }),
+        }
+    }
+
+    fn handle_restore_finished(&mut self, result: Result<Backup, String>) {
+        let backup = match result {
+            Ok(backup) => backup,
+            Err(message) => {
+                self.list_with_details.error_popup(ErrorPopup {
+                    title: "Restoring backup failed".to_string(),
+                    message,
+                });
+                return;
+            }
+        };
+
+        let backup_id = backup.id.unwrap_or_default();
+        match backup.state {
+            Some(State::RestoreCompleted) => {
+                self.enter_popup(BackupsPopup::Info(InfoPopup {
+                    title: "Backup restored".to_string(),
+                    message: format!("Backup '{backup_id}' was restored successfully"),
+                }));
+            }
+            state => {
+                let state = serde_json::to_string(&state).unwrap_or_default();
+                let fail_reason = backup
+                    .fail_reason
+                    .flatten()
+                    .unwrap_or_else(|| "No reason given".to_string());
+                self.list_with_details.error_popup(ErrorPopup {
+                    title: "Restoring backup failed".to_string(),
+                    message: format!(
+                        "Restoring backup '{backup_id}' ended in state {state}: {fail_reason}"
+                    ),
+                });
+            }
+        }
+    }
 }
 
 impl Component for BackupsTab<'_> {
     fn activate(&mut self) -> Result<()> {
+        self.start_polling();
         self.list_with_details.activate()
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(popup) = &mut self.popup {
+            if let BackupsPopup::Restore { popup, .. } = popup {
+                popup.handle_key_events(key);
+            }
+            return Ok(None);
+        }
         self.list_with_details.handle_key_events(key)
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if self.popup.is_some() {
+            match action {
+                Action::ClosePopup => {
+                    self.exit_popup();
+                    return Ok(None);
+                }
+                Action::ConfirmPopup => {
+                    self.confirm_popup();
+                    return Ok(None);
+                }
+                _ => (),
+            }
+        }
+
         let _ = self.list_with_details.update(action.clone());
 
         match action {
+            Action::RestoreBackup => {
+                self.popup_restore_confirmation();
+            }
+            Action::BackupRestoreStarted(result) => match result {
+                Ok(backup_id) => self.list_with_details.put(backup_id),
+                Err(message) => {
+                    self.restore_guard = None;
+                    self.list_with_details.error_popup(ErrorPopup {
+                        title: "Restoring backup failed".to_string(),
+                        message,
+                    })
+                }
+            },
+            Action::BackupRestoreFinished(result) => {
+                self.restore_guard = None;
+                self.handle_restore_finished(result);
+            }
+            Action::DownloadItem => {
+                self.download_backup();
+            }
+            Action::DownloadProgress {
+                item_name,
+                downloaded,
+                total,
+            } if item_name == self.item_name => {
+                if let Some(BackupsPopup::Download(popup)) = &mut self.popup {
+                    popup.set_progress(downloaded, total);
+                }
+            }
+            Action::DownloadFinished { item_name, result } if item_name == self.item_name => {
+                self.handle_download_finished(result);
+            }
+            Action::ItemsLoadingFinished {
+                item_name,
+                result: Ok(()),
+            }
+            | Action::ItemCreated {
+                item_name,
+                result: Ok(_),
+            } if item_name == self.item_name => {
+                self.start_polling();
+            }
             Action::StartBackup => {
                 let service = self.service.clone();
                 let tx = self.action_tx.clone();
@@ -100,7 +411,16 @@ impl Component for BackupsTab<'_> {
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
-        self.list_with_details.draw(f, area)
+        self.list_with_details.draw(f, area)?;
+        if let Some(popup) = &mut self.popup {
+            let popup: &mut dyn Popup = match popup {
+                BackupsPopup::Restore { popup, .. } => popup.as_mut(),
+                BackupsPopup::Download(popup) => popup,
+                BackupsPopup::Info(popup) => popup,
+            };
+            popup.draw(f, area)?;
+        }
+        Ok(())
     }
 }
 
@@ -108,4 +428,63 @@ impl TabComponent for BackupsTab<'_> {
     fn get_name(&self) -> &str {
         "Backups"
     }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
+}
+
+/// Polling keeps running while other tabs are shown and only stops when the tab is dropped,
+/// e.g. on a profile switch.
+impl Drop for BackupsTab<'_> {
+    fn drop(&mut self) {
+        if let Some(polling_task) = self.polling_task.take() {
+            polling_task.abort();
+        }
+    }
+}
+
+fn backup_notification(backup: &Backup) -> Notification {
+    let backup_id = backup.id.clone().unwrap_or_default();
+    match backup.state {
+        Some(State::Completed) => Notification::info(
+            "Backup completed".to_string(),
+            format!("Backup '{backup_id}' completed"),
+        ),
+        Some(State::RestoreCompleted) => Notification::info(
+            "Backup restored".to_string(),
+            format!("Backup '{backup_id}' was restored"),
+        ),
+        Some(State::RestoreFailed) => {
+            let fail_reason = backup
+                .fail_reason
+                .clone()
+                .flatten()
+                .unwrap_or_else(|| "No reason given".to_string());
+            Notification::error(
+                "Restoring backup failed".to_string(),
+                format!("Restoring backup '{backup_id}' failed: {fail_reason}"),
+            )
+        }
+        _ => {
+            let fail_reason = backup
+                .fail_reason
+                .clone()
+                .flatten()
+                .unwrap_or_else(|| "No reason given".to_string());
+            Notification::error(
+                "Backup failed".to_string(),
+                format!("Backup '{backup_id}' failed: {fail_reason}"),
+            )
+        }
+    }
+}
+
+enum BackupsPopup<'a> {
+    Restore {
+        backup_id: String,
+        popup: Box<TypedConfirmPopup<'a>>,
+    },
+    Download(ProgressPopup),
+    Info(InfoPopup),
 }
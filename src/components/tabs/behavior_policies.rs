@@ -12,10 +12,15 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action::{ItemCreated, ItemDeleted, ItemsLoadingFinished};
 use crate::action::ListWithDetailsAction;
-use crate::components::list_with_details::Features;
+use crate::components::list_with_details::{validate, Features};
+use crate::components::popups::input_popup::InputPopup;
+use crate::components::popups::Popup;
+use crate::components::references_pane::ReferencesPane;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
 use crate::repository::Repository;
 use crate::services::behavior_policy_service::BehaviorPolicyService;
+use crate::services::data_hub_sync_service::ItemType;
 use crate::{
     action::Action,
     components::{list_with_details::ListWithDetails, tabs::TabComponent, Component},
@@ -24,15 +29,17 @@ use crate::{
 
 pub struct BehaviorPoliciesTab<'a> {
     action_tx: UnboundedSender<Action>,
+    mode: Rc<RefCell<Mode>>,
     list_with_details: ListWithDetails<'a, BehaviorPolicy>,
+    state_popup: Option<(String, InputPopup<'a>)>,
     service: Arc<BehaviorPolicyService>,
     item_name: &'static str,
 }
 
-impl BehaviorPoliciesTab<'_> {
+impl<'a> BehaviorPoliciesTab<'a> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -44,31 +51,64 @@ impl BehaviorPoliciesTab<'_> {
         )
         .unwrap();
         let repository = Arc::new(repository);
-        let service = Arc::new(BehaviorPolicyService::new(
-            repository.clone(),
-            &hivemq_address,
-        ));
+        let service = Arc::new(BehaviorPolicyService::new(repository.clone(), &connection));
         let item_name = "Behavior Policy";
         let list_with_details = ListWithDetails::<BehaviorPolicy>::builder()
             .list_title("Behavior Policies")
             .item_name(item_name)
-            .mode(mode)
+            .mode(mode.clone())
+            .base_mode(Mode::BehaviorPoliciesTab)
             .repository(repository.clone())
+            .validation(validate::<BehaviorPolicy>)
+            .references_pane(ReferencesPane::new(sqlite_pool, ItemType::BehaviorPolicy))
             .features(
                 Features::builder()
                     .deletable()
                     .updatable()
                     .creatable()
+                    .data_hub()
                     .build(),
             )
             .build();
         BehaviorPoliciesTab {
             action_tx,
+            mode,
             list_with_details,
+            state_popup: None,
             service,
             item_name,
         }
     }
+
+    fn popup_find_clients_in_state(&mut self) {
+        let Some(policy_id) = self.list_with_details.get_selected_key().cloned() else {
+            return;
+        };
+        let popup = InputPopup::new(
+            format!("Clients in state of '{policy_id}'"),
+            "Enter the name of the FSM state, e.g. 'Connected'. \
+            Leave it empty to show the clients in any state of the policy."
+                .to_string(),
+            "State name",
+        );
+        self.state_popup = Some((policy_id, popup));
+        *self.mode.borrow_mut() = Mode::InputPopup;
+    }
+
+    fn confirm_find_clients_in_state(&mut self) {
+        let Some((policy_id, popup)) = self.state_popup.take() else {
+            return;
+        };
+        self.list_with_details.activate().unwrap();
+
+        let state_name = Some(popup.get_input()).filter(|state_name| !state_name.is_empty());
+        self.action_tx
+            .send(Action::ShowClientsInState {
+                policy_id,
+                state_name,
+            })
+            .expect("Behavior Policies: Failed to send ShowClientsInState action");
+    }
 }
 
 impl Component for BehaviorPoliciesTab<'_> {
@@ -77,10 +117,29 @@ impl Component for BehaviorPoliciesTab<'_> {
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some((_, popup)) = &mut self.state_popup {
+            popup.handle_key_events(key);
+            return Ok(None);
+        }
         self.list_with_details.handle_key_events(key)
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if self.state_popup.is_some() {
+            match action {
+                Action::ClosePopup => {
+                    self.state_popup = None;
+                    self.list_with_details.activate().unwrap();
+                    return Ok(None);
+                }
+                Action::ConfirmPopup => {
+                    self.confirm_find_clients_in_state();
+                    return Ok(None);
+                }
+                _ => (),
+            }
+        }
+
         if let Ok(Some(action)) = self.list_with_details.update(action.clone()) {
             let Action::LWD(lwd_action) = action else {
                 return Ok(Some(action));
@@ -120,6 +179,7 @@ impl Component for BehaviorPoliciesTab<'_> {
                             .expect("Behavior Policies: Failed to send ItemCreated action");
                     });
                 }
+                _ => {}
             }
         }
 
@@ -135,6 +195,15 @@ impl Component for BehaviorPoliciesTab<'_> {
                         .expect("Behavior Policies: Failed to send ItemsLoadingFinished action");
                 });
             }
+            Action::FindClientsInState => {
+                self.popup_find_clients_in_state();
+            }
+            Action::ShowItem {
+                item_type: ItemType::BehaviorPolicy,
+                id,
+            } if !self.list_with_details.show_item(&id) => {
+                return self.update(Action::LoadAllItems);
+            }
             _ => (),
         }
 
@@ -142,7 +211,11 @@ impl Component for BehaviorPoliciesTab<'_> {
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
-        self.list_with_details.draw(f, area)
+        self.list_with_details.draw(f, area)?;
+        if let Some((_, popup)) = &mut self.state_popup {
+            popup.draw(f, area)?;
+        }
+        Ok(())
     }
 }
 
@@ -150,4 +223,8 @@ impl TabComponent for BehaviorPoliciesTab<'_> {
     fn get_name(&self) -> &str {
         "Behavior Policies"
     }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
 }
@@ -1,40 +1,93 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use color_eyre::eyre::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use hivemq_openapi::models::ClientDetails;
+use itertools::Itertools;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use ratatui::layout::Rect;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 use tui::Frame;
 use Action::LoadAllItems;
 
-use crate::action::Action::ClientDetailsLoadingFinished;
+use crate::action::Action::{
+    ClientBulkActionFinished, ClientDetailsLoadingFinished, ClientDetailsLoadingProgress,
+    ClientDisconnected, ClientSessionInvalidated, ClientStateLoadingFinished,
+    ClientSubscriptionsLoadingFinished, ClientsInStateFound,
+};
 use crate::components::list_with_details::Features;
+use crate::components::notifications::Notification;
+use crate::components::popups::error_popup::ErrorPopup;
+use crate::components::popups::Popup;
+use crate::components::tabs::clients::bulk_action_popup::{
+    BulkActionPopup, BulkClientAction, BulkSummaryPopup,
+};
+use crate::components::tabs::clients::client_action_popup::{ClientAction, ClientActionPopup};
+use crate::components::tabs::clients::client_state_pane::ClientStatePane;
+use crate::components::tabs::clients::subscriptions_pane::SubscriptionsPane;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
-use crate::repository::{Repository, RepositoryError};
-use crate::services::client_details_service::ClientDetailsService;
+use crate::repository::{transform_repository_err, Repository};
+use crate::services::client_details_service::{
+    BulkResults, ClientDetailsService, ClientSubscriptions,
+};
+use crate::services::client_state_service::{ClientState, ClientStateService};
 use crate::{
     action::Action,
     components::{list_with_details::ListWithDetails, tabs::TabComponent, Component},
-    tui,
+    tui, utils,
 };
 
+mod bulk_action_popup;
+mod client_action_popup;
+mod client_pane;
+mod client_state_pane;
+mod subscriptions_pane;
+
+/// The item name the Clients tab tags the results of its tasks with.
+pub const ITEM_NAME: &str = "Client Details";
+/// The maximum number of concurrent REST calls of a bulk action.
+const BULK_ACTION_CONCURRENCY: usize = 10;
+/// The maximum number of client errors that are shown after loading the client details.
+const MAX_REPORTED_ERRORS: usize = 5;
+
 pub struct Clients<'a> {
     action_tx: UnboundedSender<Action>,
+    mode: Rc<RefCell<Mode>>,
     list_with_details: ListWithDetails<'a, ClientDetails>,
+    subscriptions_pane: SubscriptionsPane,
+    client_state_pane: ClientStatePane,
+    popup: Option<ClientsPopup<'a>>,
     service: Arc<ClientDetailsService>,
+    client_details_concurrency: usize,
+    loading_cancellation_token: Option<CancellationToken>,
+    /// Incremented for every load, so the results of replaced loads are ignored
+    loading_generation: usize,
+    /// The task of the last load, which a new load waits for, so it can not save into the new cache
+    loading_task: Option<JoinHandle<()>>,
+    /// Set if the load was cancelled by leaving the tab, so it is restarted when the tab is selected again
+    loading_interrupted: bool,
+    /// The filtered clients and the filter before a reload, so the filter is kept after the reload
+    reload_filter: Option<(Vec<String>, String)>,
     repository: Arc<Repository<ClientDetails>>,
+    subscription_repository: Arc<Repository<ClientSubscriptions>>,
+    client_state_service: Arc<ClientStateService>,
+    client_state_repository: Arc<Repository<ClientState>>,
 }
 
 impl<'a> Clients<'a> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
+        client_details_concurrency: usize,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -47,83 +100,574 @@ impl<'a> Clients<'a> {
             )
             .unwrap(),
         );
-        let client_details_service = ClientDetailsService::new(repository.clone(), &hivemq_address);
+        let subscription_repository = Arc::new(
+            Repository::<ClientSubscriptions>::init(
+                sqlite_pool,
+                "client_subscriptions",
+                |subscriptions| subscriptions.client_id.clone(),
+                "clientId",
+            )
+            .unwrap(),
+        );
+        let client_state_repository = Arc::new(
+            Repository::<ClientState>::init(
+                sqlite_pool,
+                "client_states",
+                |client_state| client_state.client_id.clone(),
+                "clientId",
+            )
+            .unwrap(),
+        );
+        let client_state_service = Arc::new(ClientStateService::new(
+            client_state_repository.clone(),
+            &connection,
+        ));
+        let client_details_service = ClientDetailsService::new(
+            repository.clone(),
+            subscription_repository.clone(),
+            &connection,
+        );
         let service = Arc::new(client_details_service);
         let list_with_details = ListWithDetails::<ClientDetails>::builder()
             .list_title("Clients")
-            .item_name("Client Details")
-            .mode(mode)
+            .item_name(ITEM_NAME)
+            .mode(mode.clone())
+            .base_mode(Mode::ClientsTab)
             .features(Features::builder().build())
             .repository(repository.clone())
             .build();
         Clients {
             action_tx,
+            mode,
             list_with_details,
+            subscriptions_pane: SubscriptionsPane::new(subscription_repository.clone()),
+            client_state_pane: ClientStatePane::new(client_state_repository.clone()),
+            popup: None,
             service,
+            client_details_concurrency,
+            loading_cancellation_token: None,
+            loading_generation: 0,
+            loading_task: None,
+            loading_interrupted: false,
+            reload_filter: None,
             repository,
+            subscription_repository,
+            client_state_service,
+            client_state_repository,
+        }
+    }
+
+    fn popup_client_action(&mut self, action: ClientAction) {
+        let Some(client_id) = self.list_with_details.get_selected_key() else {
+            return;
+        };
+        let popup = ClientActionPopup::new(action, client_id.clone());
+        self.enter_popup(ClientsPopup::ClientAction(popup));
+    }
+
+    fn popup_bulk_action(&mut self, action: BulkClientAction) {
+        let client_ids = self.list_with_details.get_item_keys();
+        if client_ids.is_empty() {
+            return;
+        }
+        let filter = self.list_with_details.get_filter().cloned();
+        let popup = BulkActionPopup::new(action, client_ids, filter);
+        self.enter_popup(ClientsPopup::BulkAction(Box::new(popup)));
+    }
+
+    fn enter_popup(&mut self, popup: ClientsPopup<'a>) {
+        *self.mode.borrow_mut() = match popup {
+            ClientsPopup::ClientAction(_) => Mode::ConfirmPopup,
+            ClientsPopup::BulkAction(_) => Mode::InputPopup,
+            ClientsPopup::BulkSummary(_) => Mode::ErrorPopup,
+        };
+        self.popup = Some(popup);
+    }
+
+    fn close_popup(&mut self) {
+        if self.popup.take().is_some() {
+            self.list_with_details.activate().unwrap();
+        }
+    }
+
+    fn confirm_popup(&mut self) {
+        match self.popup.take() {
+            Some(ClientsPopup::ClientAction(popup)) => {
+                self.list_with_details.activate().unwrap();
+                self.execute_client_action(popup);
+            }
+            Some(ClientsPopup::BulkAction(popup)) if popup.is_confirmed() => {
+                self.list_with_details.activate().unwrap();
+                self.execute_bulk_action(*popup);
+            }
+            popup => self.popup = popup,
+        }
+    }
+
+    fn execute_client_action(&self, popup: ClientActionPopup) {
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        let ClientActionPopup {
+            action,
+            client_id,
+            prevent_will_message,
+        } = popup;
+        tokio::spawn(async move {
+            let action = match action {
+                ClientAction::Disconnect => ClientDisconnected(
+                    service
+                        .disconnect_client(&client_id, prevent_will_message)
+                        .await,
+                ),
+                ClientAction::InvalidateSession => ClientSessionInvalidated(
+                    service
+                        .invalidate_client_session(&client_id, prevent_will_message)
+                        .await,
+                ),
+            };
+            tx.send(action)
+                .expect("Failed to send client action result");
+        });
+    }
+
+    fn execute_bulk_action(&self, popup: BulkActionPopup<'a>) {
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        let BulkActionPopup {
+            action,
+            client_ids,
+            prevent_will_message,
+            ..
+        } = popup;
+        tokio::spawn(async move {
+            let (title, results, reload) = match action {
+                BulkClientAction::Disconnect => {
+                    let results = service
+                        .disconnect_clients(
+                            client_ids,
+                            prevent_will_message,
+                            BULK_ACTION_CONCURRENCY,
+                        )
+                        .await;
+                    ("Disconnected clients".to_string(), Ok(results), true)
+                }
+                BulkClientAction::InvalidateSession => {
+                    let results = service
+                        .invalidate_client_sessions(
+                            client_ids,
+                            prevent_will_message,
+                            BULK_ACTION_CONCURRENCY,
+                        )
+                        .await;
+                    (
+                        "Invalidated client sessions".to_string(),
+                        Ok(results),
+                        false,
+                    )
+                }
+                BulkClientAction::Export => {
+                    let timestamp = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs())
+                        .unwrap_or_default();
+                    let path = utils::get_data_dir()
+                        .join("exports")
+                        .join(format!("clients-{timestamp}.json"));
+                    let results = service.export_clients(&client_ids, &path);
+                    let title = format!("Exported clients to {}", path.display());
+                    (title, results, false)
+                }
+            };
+            tx.send(ClientBulkActionFinished {
+                title,
+                results,
+                reload,
+            })
+            .expect("Failed to send ClientBulkActionFinished action");
+        });
+    }
+
+    fn handle_bulk_action_finished(
+        &mut self,
+        title: String,
+        results: Result<BulkResults, String>,
+        reload: bool,
+    ) {
+        if reload {
+            let filter = self
+                .list_with_details
+                .get_filter()
+                .cloned()
+                .map(|filter| (self.list_with_details.get_item_keys(), filter));
+            self.list_with_details.loading();
+            self.reload_clients();
+            self.reload_filter = filter;
+        } else if let Ok(results) = &results {
+            for (client_id, _) in results {
+                if self.repository.find_by_id(client_id).is_err() {
+                    self.list_with_details.remove(client_id.clone());
+                }
+            }
+            self.reset_details_panes();
+        }
+        self.enter_popup(ClientsPopup::BulkSummary(BulkSummaryPopup::new(
+            title, results,
+        )));
+    }
+
+    fn handle_client_action_result(&mut self, title: &str, result: Result<String, String>) {
+        match result {
+            Ok(client_id) => {
+                if self.repository.find_by_id(&client_id).is_err() {
+                    self.list_with_details.remove(client_id);
+                }
+                self.reset_details_panes();
+            }
+            Err(message) => {
+                self.list_with_details.error_popup(ErrorPopup {
+                    title: title.to_string(),
+                    message,
+                });
+            }
+        }
+    }
+
+    /// Filters the list by the clients in the state of a behavior policy.
+    /// If no client is cached yet, the clients are fetched from the broker.
+    /// A running load of all clients is cancelled, so it does not replace the filtered list.
+    fn show_clients_in_state(&mut self, policy_id: String, state_name: Option<String>) {
+        if let Some(cancellation_token) = self.loading_cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+        self.loading_generation += 1;
+        self.loading_interrupted = false;
+        self.reload_filter = None;
+        self.list_with_details.loading();
+
+        let filter = match &state_name {
+            Some(state_name) => format!("{policy_id} -> {state_name}"),
+            None => policy_id.clone(),
+        };
+        let generation = self.loading_generation;
+        let details_service = self.service.clone();
+        let service = self.client_state_service.clone();
+        let concurrency = self.client_details_concurrency;
+        let tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let result = async {
+                let client_ids = details_service.find_client_ids().await?;
+                let client_ids = service
+                    .find_clients_in_state(
+                        client_ids,
+                        &policy_id,
+                        state_name.as_deref(),
+                        concurrency,
+                    )
+                    .await?;
+                Ok(details_service
+                    .load_missing_details(client_ids, concurrency)
+                    .await)
+            }
+            .await;
+            tx.send(ClientsInStateFound {
+                generation,
+                filter,
+                result,
+            })
+            .expect("Failed to send ClientsInStateFound action");
+        });
+    }
+
+    /// Clears the cached subscriptions and client states and loads all clients again.
+    fn reload_clients(&mut self) {
+        if let Err(err) = self.subscription_repository.delete_all() {
+            log::error!("Failed to clear cached client subscriptions: {err:?}");
+        }
+        if let Err(err) = self.client_state_repository.delete_all() {
+            log::error!("Failed to clear cached client states: {err:?}");
+        }
+        self.reset_details_panes();
+
+        self.load_client_details();
+    }
+
+    fn load_client_details(&mut self) {
+        if let Some(cancellation_token) = self.loading_cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+        let cancellation_token = CancellationToken::new();
+        self.loading_cancellation_token = Some(cancellation_token.clone());
+        self.loading_generation += 1;
+        self.loading_interrupted = false;
+        self.reload_filter = None;
+
+        let generation = self.loading_generation;
+        let previous_task = self.loading_task.take();
+        let service = self.service.clone();
+        let concurrency = self.client_details_concurrency;
+        let tx = self.action_tx.clone();
+        self.loading_task = Some(tokio::spawn(async move {
+            if let Some(previous_task) = previous_task {
+                let _ = previous_task.await;
+            }
+            let progress_tx = tx.clone();
+            let result = service
+                .load_details(
+                    concurrency,
+                    cancellation_token,
+                    |client_ids, fetched, total| {
+                        progress_tx
+                            .send(ClientDetailsLoadingProgress {
+                                generation,
+                                client_ids,
+                                fetched,
+                                total,
+                            })
+                            .expect("Failed to send ClientDetailsLoadingProgress action");
+                    },
+                )
+                .await;
+            tx.send(ClientDetailsLoadingFinished { generation, result })
+                .expect("Failed to send ClientDetailsLoadingFinished action");
+        }));
+    }
+
+    fn handle_loading_finished(&mut self, result: Result<Vec<String>, String>) {
+        self.loading_cancellation_token = None;
+        let reload_filter = self.reload_filter.take();
+        let errors = match result {
+            Ok(errors) => errors,
+            Err(msg) => {
+                self.list_with_details.list_error(&msg);
+                return;
+            }
+        };
+        if !errors.is_empty() {
+            let mut message = errors.iter().take(MAX_REPORTED_ERRORS).join("\n");
+            if errors.len() > MAX_REPORTED_ERRORS {
+                message.push_str(&format!(
+                    "\n... and {} more",
+                    errors.len() - MAX_REPORTED_ERRORS
+                ));
+            }
+            self.action_tx
+                .send(Action::Notify(Notification::error(
+                    format!("Skipped {} clients", errors.len()),
+                    message,
+                )))
+                .expect("Failed to send Notify action");
+        }
+        let selected = self.list_with_details.get_selected_key().cloned();
+        match self.repository.find_all() {
+            Ok(client_details) => {
+                let items: Vec<String> = client_details
+                    .into_iter()
+                    .filter_map(|item| item.id.clone())
+                    .collect();
+                match reload_filter {
+                    // Clients that are gone after the reload are not shown anymore
+                    Some((filtered_items, filter)) => {
+                        let items: HashSet<String> = items.into_iter().collect();
+                        let filtered_items = filtered_items
+                            .into_iter()
+                            .filter(|client_id| items.contains(client_id))
+                            .collect();
+                        self.list_with_details
+                            .set_items(filtered_items, Some(filter));
+                    }
+                    None => self.list_with_details.set_items(items, None),
+                }
+                if let Some(selected) = selected {
+                    self.list_with_details.select_item(selected);
+                }
+            }
+            Err(repo_err) => {
+                self.list_with_details
+                    .list_error(&transform_repository_err(repo_err));
+            }
+        }
+    }
+
+    fn reset_details_panes(&mut self) {
+        self.subscriptions_pane.reset();
+        self.client_state_pane.reset();
+    }
+
+    fn update_details_panes(&mut self) {
+        let selected = self.list_with_details.get_selected_key().cloned();
+        let Some(client_id) = selected else {
+            self.reset_details_panes();
+            return;
+        };
+
+        if Some(client_id.as_str()) != self.subscriptions_pane.get_client_id()
+            && !self.subscriptions_pane.select_client(&client_id)
+        {
+            let service = self.service.clone();
+            let tx = self.action_tx.clone();
+            let client_id = client_id.clone();
+            tokio::spawn(async move {
+                let result = service.load_subscriptions(&client_id).await;
+                tx.send(ClientSubscriptionsLoadingFinished { client_id, result })
+                    .expect("Failed to send ClientSubscriptionsLoadingFinished action");
+            });
+        }
+
+        if Some(client_id.as_str()) != self.client_state_pane.get_client_id()
+            && !self.client_state_pane.select_client(&client_id)
+        {
+            let service = self.client_state_service.clone();
+            let tx = self.action_tx.clone();
+            tokio::spawn(async move {
+                let result = service.load_client_state(&client_id).await;
+                tx.send(ClientStateLoadingFinished { client_id, result })
+                    .expect("Failed to send ClientStateLoadingFinished action");
+            });
         }
     }
 }
 
 impl Component for Clients<'_> {
     fn activate(&mut self) -> Result<()> {
+        if self.loading_interrupted {
+            self.load_client_details();
+        }
         self.list_with_details.activate()
     }
 
+    fn deactivate(&mut self) -> Result<()> {
+        if let Some(cancellation_token) = self.loading_cancellation_token.take() {
+            cancellation_token.cancel();
+            // The cancelled load is not reported, the list keeps loading until it is restarted
+            self.loading_generation += 1;
+            self.loading_interrupted = true;
+        }
+        Ok(())
+    }
+
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(popup) = &mut self.popup {
+            match popup {
+                ClientsPopup::ClientAction(popup) => {
+                    if key.code == KeyCode::F(1) {
+                        popup.toggle_prevent_will_message();
+                    }
+                }
+                ClientsPopup::BulkAction(popup) => popup.handle_key_events(key),
+                ClientsPopup::BulkSummary(_) => (),
+            }
+            return Ok(None);
+        }
         self.list_with_details.handle_key_events(key)
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if self.popup.is_some() {
+            match action {
+                Action::ClosePopup => {
+                    self.close_popup();
+                    return Ok(None);
+                }
+                Action::ConfirmPopup => {
+                    self.confirm_popup();
+                    return Ok(None);
+                }
+                _ => (),
+            }
+        }
+
         let _ = self.list_with_details.update(action.clone());
 
         match action {
+            Action::DisconnectClient => {
+                self.popup_client_action(ClientAction::Disconnect);
+            }
+            Action::InvalidateClientSession => {
+                self.popup_client_action(ClientAction::InvalidateSession);
+            }
+            ClientDisconnected(result) => {
+                self.handle_client_action_result("Disconnecting client failed", result);
+            }
+            ClientSessionInvalidated(result) => {
+                self.handle_client_action_result("Invalidating client session failed", result);
+            }
+            Action::DisconnectAllClients => {
+                self.popup_bulk_action(BulkClientAction::Disconnect);
+            }
+            Action::InvalidateAllClientSessions => {
+                self.popup_bulk_action(BulkClientAction::InvalidateSession);
+            }
+            Action::ExportAllClients => {
+                self.popup_bulk_action(BulkClientAction::Export);
+            }
+            ClientBulkActionFinished {
+                title,
+                results,
+                reload,
+            } => {
+                self.handle_bulk_action_finished(title, results, reload);
+            }
             LoadAllItems => {
-                let service = self.service.clone();
-                let tx = self.action_tx.clone();
-                let _ = tokio::spawn(async move {
-                    let result = service.load_details().await;
-                    let action = match result {
-                        Ok(_) => ClientDetailsLoadingFinished(Ok(())),
-                        Err(msg) => ClientDetailsLoadingFinished(Err(msg)),
-                    };
-                    tx.send(action)
-                        .expect("Failed to send ClientDetailsLoadingFinished action");
-                });
+                self.reload_clients();
             }
-            ClientDetailsLoadingFinished(result) => match result {
-                Ok(_) => {
-                    let result = self.repository.find_all();
-                    match result {
-                        Ok(client_details) => {
-                            let items: Vec<String> = client_details
-                                .into_iter()
-                                .filter(|item| item.id.is_some())
-                                .map(|item| item.id.clone().unwrap())
-                                .collect();
-                            self.list_with_details.set_items(items, None);
-                        }
-                        Err(repo_err) => {
-                            let repo_err = match repo_err {
-                                RepositoryError::SerdeError(err) => err.to_string(),
-                                RepositoryError::SqlError(err) => err.to_string(),
-                            };
-                            self.list_with_details.list_error(&repo_err);
-                        }
-                    }
-                }
-                Err(msg) => {
-                    self.list_with_details.list_error(&msg);
-                }
+            ClientDetailsLoadingProgress {
+                generation,
+                client_ids,
+                fetched,
+                total,
+            } if generation == self.loading_generation => {
+                self.list_with_details
+                    .loading_progress(client_ids, fetched, total);
+            }
+            ClientDetailsLoadingFinished { generation, result }
+                if generation == self.loading_generation =>
+            {
+                self.handle_loading_finished(result);
+            }
+            ClientSubscriptionsLoadingFinished { client_id, result } => {
+                self.subscriptions_pane.loading_finished(&client_id, result);
+            }
+            ClientStateLoadingFinished { client_id, result } => {
+                self.client_state_pane.loading_finished(&client_id, result);
+            }
+            Action::ShowClientsInState {
+                policy_id,
+                state_name,
+            } => {
+                self.show_clients_in_state(policy_id, state_name);
+            }
+            ClientsInStateFound {
+                generation,
+                filter,
+                result,
+            } if generation == self.loading_generation => match result {
+                Ok(client_ids) => self.list_with_details.set_items(client_ids, Some(filter)),
+                Err(msg) => self.list_with_details.list_error(&msg),
             },
             _ => (),
         };
 
+        self.update_details_panes();
+
         Ok(None)
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
-        self.list_with_details.draw(f, area).unwrap();
+        self.list_with_details
+            .draw_with_details_panes(
+                f,
+                area,
+                &mut [&mut self.subscriptions_pane, &mut self.client_state_pane],
+            )
+            .unwrap();
+        if let Some(popup) = &mut self.popup {
+            let popup: &mut dyn Popup = match popup {
+                ClientsPopup::ClientAction(popup) => popup,
+                ClientsPopup::BulkAction(popup) => popup.as_mut(),
+                ClientsPopup::BulkSummary(popup) => popup,
+            };
+            popup.draw(f, area)?;
+        }
         Ok(())
     }
 }
@@ -132,4 +676,14 @@ impl TabComponent for Clients<'_> {
     fn get_name(&self) -> &str {
         "Clients"
     }
+
+    fn get_item_name(&self) -> &str {
+        ITEM_NAME
+    }
+}
+
+enum ClientsPopup<'a> {
+    ClientAction(ClientActionPopup),
+    BulkAction(Box<BulkActionPopup<'a>>),
+    BulkSummary(BulkSummaryPopup),
 }
@@ -0,0 +1,196 @@
+use std::ops::Not;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Alignment::Center;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::Style;
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use tui_textarea::TextArea;
+
+use crate::components::popups::{draw_default_popup, Popup};
+use crate::services::client_details_service::BulkResults;
+use crate::theme::theme;
+use crate::tui::Frame;
+
+#[derive(Clone, Copy)]
+pub enum BulkClientAction {
+    Disconnect,
+    InvalidateSession,
+    Export,
+}
+
+impl BulkClientAction {
+    fn name(&self) -> &'static str {
+        match self {
+            BulkClientAction::Disconnect => "Disconnect",
+            BulkClientAction::InvalidateSession => "Invalidate Session of",
+            BulkClientAction::Export => "Export",
+        }
+    }
+}
+
+/// Asks the user to type the number of affected clients before a bulk action is executed.
+pub struct BulkActionPopup<'a> {
+    pub action: BulkClientAction,
+    pub client_ids: Vec<String>,
+    pub prevent_will_message: bool,
+    filter: Option<String>,
+    text_area: TextArea<'a>,
+}
+
+impl<'a> BulkActionPopup<'a> {
+    pub fn new(action: BulkClientAction, client_ids: Vec<String>, filter: Option<String>) -> Self {
+        let mut text_area = TextArea::default();
+        text_area.set_cursor_line_style(Style::default());
+        text_area.set_placeholder_text(format!("Type {} to confirm", client_ids.len()));
+        text_area.set_block(Block::default().borders(Borders::ALL).title("Confirmation"));
+
+        BulkActionPopup {
+            action,
+            client_ids,
+            prevent_will_message: false,
+            filter,
+            text_area,
+        }
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.text_area.lines()[0].trim() == self.client_ids.len().to_string()
+    }
+
+    pub fn handle_key_events(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => (),
+            KeyCode::F(1) => self.prevent_will_message = self.prevent_will_message.not(),
+            _ => {
+                self.text_area.input(key);
+            }
+        }
+    }
+}
+
+impl Popup for BulkActionPopup<'_> {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let count = self.client_ids.len();
+        let block = Block::default()
+            .title(format!("{} {count} clients?", self.action.name()))
+            .title_alignment(Center)
+            .borders(Borders::ALL)
+            .border_style(theme().accent);
+        let inner = block.inner(popup_area);
+        f.render_widget(block, popup_area);
+
+        let layout = Layout::vertical([
+            Constraint::Percentage(100),
+            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .horizontal_margin(1)
+        .split(inner);
+
+        let filter = match &self.filter {
+            Some(filter) => format!("clients matching the filter '{filter}'"),
+            None => "all listed clients".to_string(),
+        };
+        let message = Paragraph::new(format!(
+            "This affects {count} {filter}. Type {count} to confirm."
+        ))
+        .wrap(Wrap { trim: true })
+        .style(theme().accent);
+        f.render_widget(message, layout[0]);
+
+        let text_area_style = if self.is_confirmed() {
+            Style::default()
+        } else {
+            theme().error
+        };
+        self.text_area.set_style(text_area_style);
+        f.render_widget(self.text_area.widget(), layout[1]);
+
+        if !matches!(self.action, BulkClientAction::Export) {
+            let checkbox = if self.prevent_will_message {
+                "(*) Prevent will message [F1]"
+            } else {
+                "( ) Prevent will message [F1]"
+            };
+            f.render_widget(Paragraph::new(checkbox), layout[2]);
+        }
+
+        let footer = Paragraph::new("[Esc] Close  [Enter] Confirm")
+            .alignment(Center)
+            .style(theme().accent);
+        f.render_widget(footer, layout[3]);
+
+        color_eyre::eyre::Ok(())
+    }
+
+    fn percent_y(&self) -> u16 {
+        50
+    }
+}
+
+/// Shows how many clients a bulk action succeeded for and why it failed for the others.
+pub struct BulkSummaryPopup {
+    title: String,
+    message: String,
+    style: Style,
+}
+
+impl BulkSummaryPopup {
+    pub fn new(title: String, results: Result<BulkResults, String>) -> Self {
+        let results = match results {
+            Ok(results) => results,
+            Err(message) => {
+                return BulkSummaryPopup {
+                    title,
+                    message,
+                    style: theme().error,
+                }
+            }
+        };
+
+        let failures: Vec<&(String, Result<(), String>)> = results
+            .iter()
+            .filter(|(_, result)| result.is_err())
+            .collect();
+        let mut message = format!(
+            "{} succeeded, {} failed",
+            results.len() - failures.len(),
+            failures.len()
+        );
+        for (client_id, result) in &failures {
+            if let Err(err) = result {
+                message.push_str(&format!("\n{client_id}: {err}"));
+            }
+        }
+
+        BulkSummaryPopup {
+            title,
+            message,
+            style: if failures.is_empty() {
+                theme().success
+            } else {
+                theme().error
+            },
+        }
+    }
+}
+
+impl Popup for BulkSummaryPopup {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        draw_default_popup(
+            f,
+            popup_area,
+            self.title.clone(),
+            self.message.clone(),
+            self.style,
+            "[Esc] Close".to_string(),
+        );
+        color_eyre::eyre::Ok(())
+    }
+
+    fn percent_y(&self) -> u16 {
+        60
+    }
+}
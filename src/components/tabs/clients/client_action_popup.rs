@@ -0,0 +1,66 @@
+use std::ops::Not;
+
+use ratatui::layout::Rect;
+
+use crate::components::popups::{draw_default_popup, Popup};
+use crate::theme::theme;
+use crate::tui::Frame;
+
+pub enum ClientAction {
+    Disconnect,
+    InvalidateSession,
+}
+
+pub struct ClientActionPopup {
+    pub action: ClientAction,
+    pub client_id: String,
+    pub prevent_will_message: bool,
+}
+
+impl ClientActionPopup {
+    pub fn new(action: ClientAction, client_id: String) -> Self {
+        ClientActionPopup {
+            action,
+            client_id,
+            prevent_will_message: false,
+        }
+    }
+
+    pub fn toggle_prevent_will_message(&mut self) {
+        self.prevent_will_message = self.prevent_will_message.not();
+    }
+}
+
+impl Popup for ClientActionPopup {
+    fn draw_popup(&mut self, f: &mut Frame<'_>, popup_area: Rect) -> color_eyre::Result<()> {
+        let (title, message) = match self.action {
+            ClientAction::Disconnect => (
+                "Disconnect client?",
+                format!("Are you sure you want to disconnect '{}'?", self.client_id),
+            ),
+            ClientAction::InvalidateSession => (
+                "Invalidate session?",
+                format!(
+                    "Are you sure you want to invalidate the session of '{}'? \
+                    The client is disconnected and its session is removed.",
+                    self.client_id
+                ),
+            ),
+        };
+        let checkbox = if self.prevent_will_message {
+            "(*) Prevent will message [F1]"
+        } else {
+            "( ) Prevent will message [F1]"
+        };
+
+        draw_default_popup(
+            f,
+            popup_area,
+            title.to_string(),
+            format!("{message}\n\n{checkbox}"),
+            theme().accent,
+            "[Esc] Close  [Enter] Confirm".to_string(),
+        );
+        color_eyre::eyre::Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::Result;
+use ratatui::{
+    layout::Rect,
+    prelude::Stylize,
+    style::Style,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{
+    components::Component,
+    repository::{transform_repository_err, Repository},
+    theme::theme,
+    tui::Frame,
+};
+
+/// The data of a single client that is shown in a [ClientPane].
+pub trait ClientPaneContent: Serialize + DeserializeOwned {
+    /// The title of the pane, e.g. `Subscriptions`.
+    const TITLE: &'static str;
+
+    fn client_id(&self) -> &str;
+
+    fn draw(&self, f: &mut Frame<'_>, area: Rect);
+}
+
+/// A details pane of the Clients tab with data of the selected client.
+/// The data is loaded lazily when a client is selected and cached in the repository.
+pub struct ClientPane<T: ClientPaneContent> {
+    repository: Arc<Repository<T>>,
+    state: ClientPaneState<T>,
+}
+
+enum ClientPaneState<T> {
+    NoClient,
+    Loading(String),
+    LoadingError { client_id: String, message: String },
+    Loaded(T),
+}
+
+impl<T: ClientPaneContent> ClientPane<T> {
+    pub fn new(repository: Arc<Repository<T>>) -> Self {
+        ClientPane {
+            repository,
+            state: ClientPaneState::NoClient,
+        }
+    }
+
+    pub fn get_client_id(&self) -> Option<&str> {
+        match &self.state {
+            ClientPaneState::NoClient => None,
+            ClientPaneState::Loading(client_id) => Some(client_id),
+            ClientPaneState::LoadingError { client_id, .. } => Some(client_id),
+            ClientPaneState::Loaded(content) => Some(content.client_id()),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state = ClientPaneState::NoClient;
+    }
+
+    /// Shows the data of the given client.
+    /// Returns false if the data is not cached yet and needs to be loaded.
+    pub fn select_client(&mut self, client_id: &str) -> bool {
+        match self.repository.find_by_id(client_id) {
+            Ok(content) => {
+                self.state = ClientPaneState::Loaded(content);
+                true
+            }
+            Err(_) => {
+                self.state = ClientPaneState::Loading(client_id.to_owned());
+                false
+            }
+        }
+    }
+
+    pub fn loading_finished(&mut self, client_id: &str, result: Result<(), String>) {
+        if self.get_client_id() != Some(client_id) {
+            return;
+        }
+
+        let result = result.and_then(|_| {
+            self.repository
+                .find_by_id(client_id)
+                .map_err(transform_repository_err)
+        });
+        self.state = match result {
+            Ok(content) => ClientPaneState::Loaded(content),
+            Err(message) => ClientPaneState::LoadingError {
+                client_id: client_id.to_owned(),
+                message,
+            },
+        };
+    }
+}
+
+impl<T: ClientPaneContent> Component for ClientPane<T> {
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        match &self.state {
+            ClientPaneState::NoClient => {
+                f.render_widget(
+                    Block::default()
+                        .style(Style::default().dim())
+                        .borders(Borders::ALL)
+                        .title(T::TITLE),
+                    area,
+                );
+            }
+            ClientPaneState::Loading(_) => {
+                f.render_widget(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(theme().accent)
+                        .title(format!("Loading {}...", T::TITLE)),
+                    area,
+                );
+            }
+            ClientPaneState::LoadingError { message, .. } => {
+                let p = Paragraph::new(message.clone())
+                    .wrap(Wrap { trim: true })
+                    .style(theme().error)
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(format!("Loading {} failed", T::TITLE)),
+                    );
+                f.render_widget(p, area);
+            }
+            ClientPaneState::Loaded(content) => content.draw(f, area),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use itertools::Itertools;
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Row, Table},
+};
+
+use crate::{
+    components::tabs::clients::client_pane::{ClientPane, ClientPaneContent},
+    services::client_state_service::ClientState,
+    tui::Frame,
+};
+
+pub type ClientStatePane = ClientPane<ClientState>;
+
+impl ClientPaneContent for ClientState {
+    const TITLE: &'static str = "Data Hub State";
+
+    fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    fn draw(&self, f: &mut Frame<'_>, area: Rect) {
+        let rows: Vec<Row> = self
+            .states
+            .iter()
+            .map(|state| {
+                let variables = state
+                    .variables
+                    .iter()
+                    .flatten()
+                    .sorted()
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .join(", ");
+                Row::new(vec![
+                    state.policy_id.clone().unwrap_or_default(),
+                    state.state_name.clone().unwrap_or_default(),
+                    state.state_type.clone().unwrap_or_default(),
+                    state.first_set_at.clone().unwrap_or_default(),
+                    variables,
+                ])
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(12),
+            Constraint::Length(24),
+            Constraint::Fill(1),
+        ];
+        let header = Row::new(vec!["Policy", "State", "Type", "Since", "Variables"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let title = format!("{} ({})", Self::TITLE, self.states.len());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(table, area);
+    }
+}
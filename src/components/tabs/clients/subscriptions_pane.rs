@@ -0,0 +1,91 @@
+use hivemq_openapi::models::client_subscription::{Qos, RetainHandling};
+use ratatui::{
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Row, Table},
+};
+use serde::Serialize;
+
+use crate::{
+    components::tabs::clients::client_pane::{ClientPane, ClientPaneContent},
+    services::client_details_service::ClientSubscriptions,
+    tui::Frame,
+};
+
+pub type SubscriptionsPane = ClientPane<ClientSubscriptions>;
+
+impl ClientPaneContent for ClientSubscriptions {
+    const TITLE: &'static str = "Subscriptions";
+
+    fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    fn draw(&self, f: &mut Frame<'_>, area: Rect) {
+        let rows: Vec<Row> = self
+            .subscriptions
+            .iter()
+            .map(|subscription| {
+                Row::new(vec![
+                    subscription.topic_filter.clone().unwrap_or_default(),
+                    subscription.qos.map(qos_to_string).unwrap_or_default(),
+                    to_string(&subscription.no_local),
+                    to_string(&subscription.retain_as_published),
+                    subscription
+                        .retain_handling
+                        .map(retain_handling_to_string)
+                        .unwrap_or_default(),
+                    to_string(&subscription.subscription_identifier.flatten()),
+                ])
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(15),
+            Constraint::Length(6),
+        ];
+        let header = Row::new(vec![
+            "Topic Filter",
+            "QoS",
+            "No Local",
+            "Retain As",
+            "Retain Handling",
+            "Sub Id",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD));
+        let title = format!("{} ({})", Self::TITLE, self.subscriptions.len());
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::ALL).title(title));
+        f.render_widget(table, area);
+    }
+}
+
+fn qos_to_string(qos: Qos) -> String {
+    match qos {
+        Qos::AtMostOnce => "0",
+        Qos::AtLeastOnce => "1",
+        Qos::ExactlyOnce => "2",
+    }
+    .to_string()
+}
+
+fn retain_handling_to_string(retain_handling: RetainHandling) -> String {
+    match retain_handling {
+        RetainHandling::Send => "Send",
+        RetainHandling::SendIfNewSubscription => "If New",
+        RetainHandling::DoNotSend => "Do Not Send",
+    }
+    .to_string()
+}
+
+fn to_string<T: Serialize>(value: &Option<T>) -> String {
+    match value {
+        None => String::new(),
+        Some(value) => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
@@ -12,9 +12,12 @@ use tokio::sync::mpsc::UnboundedSender;
 
 use crate::action::Action::{ItemCreated, ItemDeleted, ItemsLoadingFinished};
 use crate::action::ListWithDetailsAction;
-use crate::components::list_with_details::Features;
+use crate::components::list_with_details::{validate, Features};
+use crate::components::references_pane::ReferencesPane;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
 use crate::repository::Repository;
+use crate::services::data_hub_sync_service::ItemType;
 use crate::services::data_policy_service::DataPolicyService;
 use crate::{
     action::Action,
@@ -32,7 +35,7 @@ pub struct DataPoliciesTab<'a> {
 impl DataPoliciesTab<'_> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -44,18 +47,22 @@ impl DataPoliciesTab<'_> {
         )
         .unwrap();
         let repository = Arc::new(repository);
-        let service = Arc::new(DataPolicyService::new(repository.clone(), &hivemq_address));
+        let service = Arc::new(DataPolicyService::new(repository.clone(), &connection));
         let item_name = "Data Policy";
         let list_with_details = ListWithDetails::<DataPolicy>::builder()
             .list_title("Data Policies")
             .item_name("Data Policy")
             .mode(mode)
+            .base_mode(Mode::DataHubTab)
             .repository(repository.clone())
+            .validation(validate::<DataPolicy>)
+            .references_pane(ReferencesPane::new(sqlite_pool, ItemType::DataPolicy))
             .features(
                 Features::builder()
                     .deletable()
                     .creatable()
                     .updatable()
+                    .data_hub()
                     .build(),
             )
             .build();
@@ -117,6 +124,7 @@ impl Component for DataPoliciesTab<'_> {
                             .expect("Data Policies: Failed to send ItemCreated action");
                     });
                 }
+                _ => {}
             }
         }
 
@@ -132,6 +140,12 @@ impl Component for DataPoliciesTab<'_> {
                         .expect("Data Policies: Failed to send ItemsLoadingFinished action");
                 });
             }
+            Action::ShowItem {
+                item_type: ItemType::DataPolicy,
+                id,
+            } if !self.list_with_details.show_item(&id) => {
+                return self.update(Action::LoadAllItems);
+            }
             _ => (),
         }
 
@@ -147,4 +161,8 @@ impl TabComponent for DataPoliciesTab<'_> {
     fn get_name(&self) -> &str {
         "Data Policies"
     }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
 }
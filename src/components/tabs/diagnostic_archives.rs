@@ -0,0 +1,106 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use hivemq_openapi::models::DiagnosticArchive;
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use ratatui::layout::Rect;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::components::list_with_details::Features;
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::mode::Mode;
+use crate::repository::Repository;
+use crate::services::diagnostic_archive_service::DiagnosticArchiveService;
+use crate::{
+    action::Action,
+    components::{list_with_details::ListWithDetails, tabs::TabComponent, Component},
+    tui::Frame,
+};
+
+pub struct DiagnosticArchivesTab<'a> {
+    action_tx: UnboundedSender<Action>,
+    list_with_details: ListWithDetails<'a, DiagnosticArchive>,
+    service: Arc<DiagnosticArchiveService>,
+    item_name: &'static str,
+}
+
+impl DiagnosticArchivesTab<'_> {
+    pub fn new(
+        action_tx: UnboundedSender<Action>,
+        connection: HivemqConnection,
+        mode: Rc<RefCell<Mode>>,
+        sqlite_pool: &Pool<SqliteConnectionManager>,
+    ) -> Self {
+        let repository = Repository::<DiagnosticArchive>::init(
+            sqlite_pool,
+            "diagnostic_archives",
+            |val| val.id.clone().unwrap(),
+            "createdAt",
+        )
+        .unwrap();
+        let repository = Arc::new(repository);
+        let service = Arc::new(DiagnosticArchiveService::new(
+            repository.clone(),
+            &connection,
+        ));
+        let item_name = "Diagnostic Archive";
+        let list_with_details = ListWithDetails::<DiagnosticArchive>::builder()
+            .list_title("Diagnostic Archives")
+            .item_name(item_name)
+            .mode(mode)
+            .base_mode(Mode::DiagnosticArchivesTab)
+            .repository(repository.clone())
+            .features(Features::builder().build())
+            .build();
+        DiagnosticArchivesTab {
+            action_tx,
+            list_with_details,
+            service,
+            item_name,
+        }
+    }
+}
+
+impl Component for DiagnosticArchivesTab<'_> {
+    fn activate(&mut self) -> Result<()> {
+        self.list_with_details.activate()
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        self.list_with_details.handle_key_events(key)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let _ = self.list_with_details.update(action.clone());
+
+        if let Action::CreateDiagnosticArchive = action {
+            let service = self.service.clone();
+            let tx = self.action_tx.clone();
+            let item_name = self.item_name.to_string();
+            tokio::spawn(async move {
+                let result = service.create_diagnostic_archive().await;
+                tx.send(Action::ItemCreated { item_name, result }).unwrap();
+            });
+        }
+
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        self.list_with_details.draw(f, area)
+    }
+}
+
+impl TabComponent for DiagnosticArchivesTab<'_> {
+    fn get_name(&self) -> &str {
+        "Diagnostic Archives"
+    }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
+}
@@ -10,11 +10,17 @@ use r2d2_sqlite::SqliteConnectionManager;
 use ratatui::layout::Rect;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::action::Action::{ItemCreated, ItemDeleted, ItemsLoadingFinished};
+use crate::action::Action::{
+    ItemCreated, ItemDeleted, ItemsLoadingFinished, ReferencingPoliciesLoaded,
+};
 use crate::action::ListWithDetailsAction;
-use crate::components::list_with_details::Features;
+use crate::components::list_with_details::{validate, Features};
+use crate::components::references_pane::ReferencesPane;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
+use crate::references::PolicyLoader;
 use crate::repository::Repository;
+use crate::services::data_hub_sync_service::ItemType;
 use crate::services::schema_service::SchemaService;
 use crate::{
     action::Action,
@@ -26,13 +32,14 @@ pub struct SchemasTab<'a> {
     action_tx: UnboundedSender<Action>,
     list_with_details: ListWithDetails<'a, Schema>,
     service: Arc<SchemaService>,
+    policy_loader: Arc<PolicyLoader>,
     item_name: &'static str,
 }
 
 impl SchemasTab<'_> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -40,20 +47,30 @@ impl SchemasTab<'_> {
             Repository::<Schema>::init(sqlite_pool, "schemas", |val| val.id.clone(), "createdAt")
                 .unwrap();
         let repository = Arc::new(repository);
-        let service = Arc::new(SchemaService::new(repository.clone(), &hivemq_address));
+        let service = Arc::new(SchemaService::new(repository.clone(), &connection));
         let item_name = "Schema";
         let list_with_details = ListWithDetails::<Schema>::builder()
             .list_title("Schemas")
             .item_name(item_name)
             .mode(mode)
+            .base_mode(Mode::DataHubTab)
             .repository(repository)
-            .features(Features::builder().creatable().deletable().build())
+            .validation(validate::<Schema>)
+            .references_pane(ReferencesPane::new(sqlite_pool, ItemType::Schema))
+            .features(
+                Features::builder()
+                    .creatable()
+                    .deletable()
+                    .data_hub()
+                    .build(),
+            )
             .build();
 
         SchemasTab {
             action_tx,
             list_with_details,
             service,
+            policy_loader: Arc::new(PolicyLoader::new(sqlite_pool, &connection)),
             item_name,
         }
     }
@@ -97,6 +114,21 @@ impl Component for SchemasTab<'_> {
                             .expect("Schemas: Failed to send ItemCreated action");
                     });
                 }
+                ListWithDetailsAction::LoadReferencingPolicies(item_id) => {
+                    let policy_loader = self.policy_loader.clone();
+                    let tx = self.action_tx.clone();
+                    let item_name = String::from(self.item_name);
+                    tokio::spawn(async move {
+                        let result = policy_loader.load().await;
+                        let action = ReferencingPoliciesLoaded {
+                            item_name,
+                            item_id,
+                            result,
+                        };
+                        tx.send(action)
+                            .expect("Schemas: Failed to send ReferencingPoliciesLoaded action");
+                    });
+                }
                 _ => {}
             }
         }
@@ -113,6 +145,12 @@ impl Component for SchemasTab<'_> {
                         .expect("Schemas: Failed to send ItemsLoadingFinished action");
                 });
             }
+            Action::ShowItem {
+                item_type: ItemType::Schema,
+                id,
+            } if !self.list_with_details.show_item(&id) => {
+                return self.update(Action::LoadAllItems);
+            }
             _ => (),
         }
 
@@ -128,4 +166,8 @@ impl TabComponent for SchemasTab<'_> {
     fn get_name(&self) -> &str {
         "Schemas"
     }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
 }
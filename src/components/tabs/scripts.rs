@@ -10,11 +10,17 @@ use r2d2_sqlite::SqliteConnectionManager;
 use ratatui::layout::Rect;
 use tokio::sync::mpsc::UnboundedSender;
 
-use crate::action::Action::{ItemCreated, ItemDeleted, ItemsLoadingFinished};
+use crate::action::Action::{
+    ItemCreated, ItemDeleted, ItemsLoadingFinished, ReferencingPoliciesLoaded,
+};
 use crate::action::ListWithDetailsAction;
-use crate::components::list_with_details::Features;
+use crate::components::list_with_details::{validate, Features};
+use crate::components::references_pane::ReferencesPane;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
+use crate::references::PolicyLoader;
 use crate::repository::Repository;
+use crate::services::data_hub_sync_service::ItemType;
 use crate::services::scripts_service::ScriptService;
 use crate::{
     action::Action,
@@ -26,13 +32,14 @@ pub struct ScriptsTab<'a> {
     action_tx: UnboundedSender<Action>,
     list_with_details: ListWithDetails<'a, Script>,
     service: Arc<ScriptService>,
+    policy_loader: Arc<PolicyLoader>,
     item_name: &'static str,
 }
 
 impl ScriptsTab<'_> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -40,19 +47,29 @@ impl ScriptsTab<'_> {
             Repository::<Script>::init(sqlite_pool, "scripts", |val| val.id.clone(), "createdAt")
                 .unwrap();
         let repository = Arc::new(repository);
-        let service = Arc::new(ScriptService::new(repository.clone(), &hivemq_address));
+        let service = Arc::new(ScriptService::new(repository.clone(), &connection));
         let item_name = "Script";
         let list_with_details = ListWithDetails::<Script>::builder()
             .list_title("Scripts")
             .item_name("Script")
             .mode(mode)
+            .base_mode(Mode::DataHubTab)
             .repository(repository.clone())
-            .features(Features::builder().creatable().deletable().build())
+            .validation(validate::<Script>)
+            .references_pane(ReferencesPane::new(sqlite_pool, ItemType::Script))
+            .features(
+                Features::builder()
+                    .creatable()
+                    .deletable()
+                    .data_hub()
+                    .build(),
+            )
             .build();
         ScriptsTab {
             action_tx,
             list_with_details,
             service,
+            policy_loader: Arc::new(PolicyLoader::new(sqlite_pool, &connection)),
             item_name,
         }
     }
@@ -96,6 +113,21 @@ impl Component for ScriptsTab<'_> {
                             .expect("Scripts: Failed to send ItemCreated action");
                     });
                 }
+                ListWithDetailsAction::LoadReferencingPolicies(item_id) => {
+                    let policy_loader = self.policy_loader.clone();
+                    let tx = self.action_tx.clone();
+                    let item_name = String::from(self.item_name);
+                    tokio::spawn(async move {
+                        let result = policy_loader.load().await;
+                        let action = ReferencingPoliciesLoaded {
+                            item_name,
+                            item_id,
+                            result,
+                        };
+                        tx.send(action)
+                            .expect("Scripts: Failed to send ReferencingPoliciesLoaded action");
+                    });
+                }
                 _ => {}
             }
         }
@@ -112,6 +144,12 @@ impl Component for ScriptsTab<'_> {
                         .expect("Scripts: Failed to send ItemsLoadingFinished action");
                 });
             }
+            Action::ShowItem {
+                item_type: ItemType::Script,
+                id,
+            } if !self.list_with_details.show_item(&id) => {
+                return self.update(Action::LoadAllItems);
+            }
             _ => (),
         }
 
@@ -127,4 +165,8 @@ impl TabComponent for ScriptsTab<'_> {
     fn get_name(&self) -> &str {
         "Scripts"
     }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
 }
@@ -1,18 +1,28 @@
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
-use hivemq_openapi::models::TraceRecording;
+use hivemq_openapi::models::trace_recording::State;
+use hivemq_openapi::models::{TraceRecording, TraceRecordingItem};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
 use ratatui::layout::Rect;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
 
 use crate::action::Action::{ItemCreated, ItemDeleted, ItemsLoadingFinished};
 use crate::action::ListWithDetailsAction;
-use crate::components::list_with_details::Features;
+use crate::components::list_with_details::{validate, Features};
+use crate::components::notifications::Notification;
+use crate::components::popups::error_popup::ErrorPopup;
+use crate::components::popups::info_popup::InfoPopup;
+use crate::components::popups::progress_popup::ProgressPopup;
+use crate::components::popups::Popup;
+use crate::hivemq_rest_client::HivemqConnection;
 use crate::mode::Mode;
 use crate::repository::Repository;
 use crate::services::trace_recordings_service::TraceRecordingService;
@@ -22,17 +32,29 @@ use crate::{
     tui::Frame,
 };
 
+/// The item name the Trace Recordings tab tags the results of its tasks with.
+pub const ITEM_NAME: &str = "Trace Recording";
+/// The interval in which the trace recordings are polled while a trace recording is scheduled or in progress.
+const RUNNING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The number of failed polls in a row after which polling stops.
+const MAX_POLL_ERRORS: usize = 5;
+
 pub struct TraceRecordingsTab<'a> {
     action_tx: UnboundedSender<Action>,
+    mode: Rc<RefCell<Mode>>,
     list_with_details: ListWithDetails<'a, TraceRecording>,
+    popup: Option<TraceRecordingsPopup>,
     service: Arc<TraceRecordingService>,
+    download_dir: PathBuf,
+    polling_task: Option<JoinHandle<()>>,
     item_name: &'static str,
 }
 
 impl TraceRecordingsTab<'_> {
     pub fn new(
         action_tx: UnboundedSender<Action>,
-        hivemq_address: String,
+        connection: HivemqConnection,
+        download_dir: PathBuf,
         mode: Rc<RefCell<Mode>>,
         sqlite_pool: &Pool<SqliteConnectionManager>,
     ) -> Self {
@@ -44,37 +66,164 @@ impl TraceRecordingsTab<'_> {
         )
         .unwrap();
         let repository = Arc::new(repository);
-        let service = Arc::new(TraceRecordingService::new(
-            repository.clone(),
-            &hivemq_address,
-        ));
-        let item_name = "Trace Recording";
+        let service = Arc::new(TraceRecordingService::new(repository.clone(), &connection));
+        let item_name = ITEM_NAME;
         let list_with_details = ListWithDetails::<TraceRecording>::builder()
             .list_title("Trace Recordings")
             .item_name(item_name)
-            .mode(mode)
+            .mode(mode.clone())
+            .base_mode(Mode::TraceRecordingsTab)
             .repository(repository.clone())
+            .validation(validate::<TraceRecordingItem>)
             .features(Features::builder().deletable().creatable().build())
             .build();
         TraceRecordingsTab {
             action_tx,
+            mode,
             list_with_details,
+            popup: None,
             service,
+            download_dir,
+            polling_task: None,
             item_name,
         }
     }
+
+    fn enter_popup(&mut self, popup: TraceRecordingsPopup) {
+        *self.mode.borrow_mut() = Mode::ErrorPopup;
+        self.popup = Some(popup);
+    }
+
+    fn exit_popup(&mut self) {
+        if self.popup.take().is_some() {
+            self.list_with_details.activate().unwrap();
+        }
+    }
+
+    /// Keeps refreshing the trace recordings in the background
+    /// as long as any trace recording is scheduled or in progress.
+    fn start_polling(&mut self) {
+        if self
+            .polling_task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            return;
+        }
+
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        let item_name = self.item_name.to_string();
+        self.polling_task = Some(tokio::spawn(async move {
+            let mut errors = 0;
+            while service.has_running_trace_recordings() {
+                tokio::time::sleep(RUNNING_POLL_INTERVAL).await;
+                let (finished, removed) = match service.refresh_running_trace_recordings().await {
+                    Ok(refreshed) => {
+                        errors = 0;
+                        refreshed
+                    }
+                    Err(message) => {
+                        log::warn!("Polling trace recordings failed: {message}");
+                        errors += 1;
+                        if errors >= MAX_POLL_ERRORS {
+                            let _ = tx.send(Action::Notify(Notification::error(
+                                "Refreshing trace recordings failed".to_string(),
+                                format!(
+                                    "Stopped refreshing the running trace recordings: {message}"
+                                ),
+                            )));
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                for trace_recording in finished {
+                    let _ = tx.send(Action::Notify(trace_recording_notification(
+                        &trace_recording,
+                    )));
+                }
+                for name in removed {
+                    let _ = tx.send(Action::Notify(Notification::info(
+                        "Trace recording removed".to_string(),
+                        format!("Trace recording '{name}' is not on the broker anymore"),
+                    )));
+                    let _ = tx.send(Action::ItemDeleted {
+                        item_name: item_name.clone(),
+                        result: Ok(name),
+                    });
+                }
+            }
+        }));
+    }
+
+    fn download_trace_recording(&mut self) {
+        let Some(name) = self.list_with_details.get_selected_key().cloned() else {
+            return;
+        };
+        self.enter_popup(TraceRecordingsPopup::Download(ProgressPopup::new(
+            "Downloading trace recording".to_string(),
+            format!("Downloading '{name}' to {}", self.download_dir.display()),
+        )));
+
+        let service = self.service.clone();
+        let tx = self.action_tx.clone();
+        let directory = self.download_dir.clone();
+        let item_name = self.item_name.to_string();
+        tokio::spawn(async move {
+            let result = service
+                .download_trace_recording(&name, &directory, |downloaded, total| {
+                    let item_name = item_name.clone();
+                    tx.send(Action::DownloadProgress {
+                        item_name,
+                        downloaded,
+                        total,
+                    })
+                    .expect("Trace Recordings: Failed to send DownloadProgress action");
+                })
+                .await
+                .map(|path| path.display().to_string());
+            tx.send(Action::DownloadFinished { item_name, result })
+                .expect("Trace Recordings: Failed to send DownloadFinished action");
+        });
+    }
+
+    fn handle_download_finished(&mut self, result: Result<String, String>) {
+        if let Some(TraceRecordingsPopup::Download(_)) = self.popup {
+            self.exit_popup();
+        }
+        match result {
+            Ok(path) => self.enter_popup(TraceRecordingsPopup::Info(InfoPopup {
+                title: "Trace recording downloaded".to_string(),
+                message: format!("The trace recording was saved to {path}"),
+            })),
+            Err(message) => self.list_with_details.error_popup(ErrorPopup {
+                title: "Downloading trace recording failed".to_string(),
+                message,
+            }),
+        }
+    }
 }
 
 impl Component for TraceRecordingsTab<'_> {
     fn activate(&mut self) -> Result<()> {
+        self.start_polling();
         self.list_with_details.activate()
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.popup.is_some() {
+            return Ok(None);
+        }
         self.list_with_details.handle_key_events(key)
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if self.popup.is_some() && action == Action::ClosePopup {
+            self.exit_popup();
+            return Ok(None);
+        }
+
         if let Ok(Some(action)) = self.list_with_details.update(action.clone()) {
             let Action::LWD(lwd_action) = action else {
                 return Ok(Some(action));
@@ -119,6 +268,53 @@ impl Component for TraceRecordingsTab<'_> {
                         .expect("Trace Recordings: Failed to send ItemsLoadingFinished action");
                 });
             }
+            Action::ItemsLoadingFinished {
+                item_name,
+                result: Ok(()),
+            }
+            | Action::ItemCreated {
+                item_name,
+                result: Ok(_),
+            } if item_name == self.item_name => {
+                self.start_polling();
+            }
+            Action::StopTraceRecording => {
+                if let Some(name) = self.list_with_details.get_selected_key().cloned() {
+                    let service = self.service.clone();
+                    let tx = self.action_tx.clone();
+                    tokio::spawn(async move {
+                        let result = service.stop_trace_recording(&name).await;
+                        tx.send(Action::TraceRecordingStopped(result)).expect(
+                            "Trace Recordings: Failed to send TraceRecordingStopped action",
+                        );
+                    });
+                }
+            }
+            Action::TraceRecordingStopped(result) => match result {
+                Ok(name) => {
+                    self.list_with_details.put(name.clone());
+                    self.list_with_details.select_item(name);
+                }
+                Err(message) => self.list_with_details.error_popup(ErrorPopup {
+                    title: "Stopping trace recording failed".to_string(),
+                    message,
+                }),
+            },
+            Action::DownloadItem => {
+                self.download_trace_recording();
+            }
+            Action::DownloadProgress {
+                item_name,
+                downloaded,
+                total,
+            } if item_name == self.item_name => {
+                if let Some(TraceRecordingsPopup::Download(popup)) = &mut self.popup {
+                    popup.set_progress(downloaded, total);
+                }
+            }
+            Action::DownloadFinished { item_name, result } if item_name == self.item_name => {
+                self.handle_download_finished(result);
+            }
             _ => (),
         }
 
@@ -127,6 +323,13 @@ impl Component for TraceRecordingsTab<'_> {
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         self.list_with_details.draw(f, area).unwrap();
+        if let Some(popup) = &mut self.popup {
+            let popup: &mut dyn Popup = match popup {
+                TraceRecordingsPopup::Download(popup) => popup,
+                TraceRecordingsPopup::Info(popup) => popup,
+            };
+            popup.draw(f, area)?;
+        }
         Ok(())
     }
 }
@@ -135,4 +338,37 @@ impl TabComponent for TraceRecordingsTab<'_> {
     fn get_name(&self) -> &str {
         "Trace Recordings"
     }
+
+    fn get_item_name(&self) -> &str {
+        self.item_name
+    }
+}
+
+/// Polling keeps running while other tabs are shown and only stops when the tab is dropped,
+/// e.g. on a profile switch.
+impl Drop for TraceRecordingsTab<'_> {
+    fn drop(&mut self) {
+        if let Some(polling_task) = self.polling_task.take() {
+            polling_task.abort();
+        }
+    }
+}
+
+fn trace_recording_notification(trace_recording: &TraceRecording) -> Notification {
+    let name = trace_recording.name.clone().unwrap_or_default();
+    match trace_recording.state {
+        Some(State::Stopped) => Notification::info(
+            "Trace recording completed".to_string(),
+            format!("Trace recording '{name}' completed"),
+        ),
+        _ => Notification::error(
+            "Trace recording failed".to_string(),
+            format!("Trace recording '{name}' was aborted"),
+        ),
+    }
+}
+
+enum TraceRecordingsPopup {
+    Download(ProgressPopup),
+    Info(InfoPopup),
 }
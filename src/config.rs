@@ -7,9 +7,17 @@ use derive_deref::{Deref, DerefMut};
 use indexmap::IndexMap;
 use itertools::Itertools;
 use ratatui::style::{Color, Modifier, Style};
-use serde::{de::Deserializer, Deserialize};
-
-use crate::{action::Action, mode::Mode};
+use serde::{
+    de::{self, value::StrDeserializer, Deserializer},
+    Deserialize,
+};
+
+use crate::{
+    action::Action,
+    hivemq_rest_client::{Authentication, HivemqConnection, TlsConfig},
+    mode::Mode,
+    theme::{Theme, ThemeName},
+};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
@@ -19,6 +27,71 @@ pub struct AppConfig {
     pub _data_dir: PathBuf,
     #[serde(default)]
     pub _config_dir: PathBuf,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    #[serde(default)]
+    pub profiles: Vec<ProfileConfig>,
+    #[serde(default)]
+    pub theme: ThemeName,
+}
+
+impl AppConfig {
+    pub fn find_profile(&self, name: &str) -> Option<&ProfileConfig> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+}
+
+/// Credentials for the REST API, e.g. if it is only reachable through an authenticating reverse proxy.
+/// The username and password are used for basic authentication, the token for bearer authentication.
+#[derive(Clone, Debug, Deserialize, Default, PartialEq)]
+pub struct AuthConfig {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+}
+
+impl AuthConfig {
+    pub fn authentication(&self) -> Result<Option<Authentication>, String> {
+        match (&self.username, &self.password, &self.token) {
+            (Some(_), _, Some(_)) => {
+                Err("Basic and bearer authentication can not be used at the same time".to_string())
+            }
+            (None, Some(_), _) => Err("A password requires a username".to_string()),
+            (Some(username), password, None) => Ok(Some(Authentication::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            })),
+            (None, None, Some(token)) => Ok(Some(Authentication::Bearer(token.clone()))),
+            (None, None, None) => Ok(None),
+        }
+    }
+}
+
+/// A named broker the user can switch to, e.g. a dev, staging or production cluster.
+/// Data can not be changed on the broker of a read-only profile.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct ProfileConfig {
+    pub name: String,
+    /// The address of the REST API including the port, e.g. `http://localhost:8888`.
+    pub host: String,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub tls: TlsConfig,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl ProfileConfig {
+    pub fn connection(&self) -> Result<HivemqConnection, String> {
+        HivemqConnection::connect(
+            self.host.clone(),
+            self.auth.authentication()?,
+            self.tls.clone(),
+        )
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -29,11 +102,13 @@ pub struct Config {
     pub keybindings: KeyBindings,
     #[serde(default)]
     pub styles: Styles,
+    #[serde(skip)]
+    pub theme: Theme,
 }
 
 impl Config {
     pub fn new() -> Result<Self, config::ConfigError> {
-        let default_config: Config = json5::from_str(CONFIG).unwrap();
+        let default_config: RawConfig = json5::from_str(CONFIG).unwrap();
         let data_dir = crate::utils::get_data_dir();
         let config_dir = crate::utils::get_config_dir();
         let mut builder = config::Config::builder()
@@ -62,61 +137,147 @@ impl Config {
             log::debug!("No configuration file found. Using default config.");
         }
 
-        Ok(default_config)
+        let user_config: RawConfig = builder.build()?.try_deserialize()?;
+        Config::merge(default_config, user_config).map_err(config::ConfigError::Message)
+    }
+
+    /// Merges the user's config on top of the defaults.
+    /// Keybindings and styles are merged per mode, so only the keys that differ have to be configured.
+    fn merge(default_config: RawConfig, user_config: RawConfig) -> Result<Self, String> {
+        let mode_names: Vec<String> = default_config.keybindings.keys().cloned().collect();
+
+        let mut keybindings = default_config.keybindings;
+        for (mode, mut user_value) in user_config.keybindings {
+            let mode = canonical_mode_name(&mode_names, &mode);
+            if let Some(extends) = &mut user_value.extends {
+                for extended_mode in extends.iter_mut() {
+                    *extended_mode = canonical_mode_name(&mode_names, extended_mode);
+                }
+            }
+            match keybindings.get_mut(&mode) {
+                Some(mode_value) => mode_value.merge(user_value),
+                None => {
+                    keybindings.insert(mode, user_value);
+                }
+            }
+        }
+
+        let mut styles = default_config.styles;
+        for (mode, user_styles) in user_config.styles {
+            let mode = canonical_mode_name(&mode_names, &mode);
+            styles.entry(mode).or_default().extend(user_styles);
+        }
+        // The theme applies to all modes, so it is only configured once
+        if let Some(mode) = styles.keys().find(|mode| *mode != "Home") {
+            return Err(format!(
+                "styles.{mode}: Styles can only be configured for the mode 'Home'"
+            ));
+        }
+
+        let styles = Styles::from_modes(styles)?;
+        let theme = Theme::new(user_config.config.theme)
+            .with_styles(styles.get(&Mode::Home).unwrap_or(&HashMap::new()))
+            .map_err(|err| format!("styles.Home: {err}"))?;
+
+        Ok(Config {
+            config: user_config.config,
+            keybindings: KeyBindings::from_modes(keybindings)?,
+            styles,
+            theme,
+        })
     }
 }
 
+/// The config as it is written in the config files, before the keybindings and styles are validated.
+#[derive(Clone, Debug, Default, Deserialize)]
+struct RawConfig {
+    #[serde(default, flatten)]
+    config: AppConfig,
+    #[serde(default)]
+    keybindings: IndexMap<String, ModeValue>,
+    #[serde(default)]
+    styles: IndexMap<String, HashMap<String, String>>,
+}
+
+/// The config crate lowercases all keys of the user's config files,
+/// so the names of the modes are matched case-insensitively.
+fn canonical_mode_name(mode_names: &[String], name: &str) -> String {
+    mode_names
+        .iter()
+        .find(|mode_name| mode_name.eq_ignore_ascii_case(name))
+        .cloned()
+        .unwrap_or_else(|| name.to_string())
+}
+
+fn parse_mode(name: &str) -> Result<Mode, String> {
+    Mode::deserialize(StrDeserializer::<serde::de::value::Error>::new(name))
+        .map_err(|_| format!("Unknown mode '{name}'"))
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct KeyBindings {
     pub bindings: HashMap<Mode, HashMap<Vec<KeyEvent>, Action>>,
     pub display_names: HashMap<Mode, Vec<String>>,
 }
 
-impl<'de> Deserialize<'de> for KeyBindings {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let parsed_map = IndexMap::<Mode, ModeValue>::deserialize(deserializer)?;
-        let modes: IndexMap<Mode, Vec<ModeValue>> = parsed_map
-            .keys()
-            .map(|mode| (mode.clone(), collect_modes(&parsed_map, mode)))
-            .collect();
+impl KeyBindings {
+    /// Resolves the keybindings of every mode including the ones of the modes it extends.
+    /// The errors name the mode and key that is invalid, e.g. `keybindings.Tab.<x>: ...`.
+    fn from_modes(modes: IndexMap<String, ModeValue>) -> Result<Self, String> {
         let mut bindings: HashMap<Mode, HashMap<Vec<KeyEvent>, Action>> = HashMap::new();
         let mut events_to_display_names: HashMap<Mode, IndexMap<Vec<KeyEvent>, String>> =
             HashMap::new();
-        for (mode, mode_values) in modes {
+        for mode_name in modes.keys() {
+            let mode =
+                parse_mode(mode_name).map_err(|err| format!("keybindings.{mode_name}: {err}"))?;
+            let mode_values = collect_modes(&modes, mode_name, &mut Vec::new())?;
             let mut events_to_action: HashMap<Vec<KeyEvent>, Action> = HashMap::new();
             let mut events_to_display_name: IndexMap<Vec<KeyEvent>, String> = IndexMap::new();
 
             let groups: IndexMap<String, GroupValue> = mode_values
                 .iter()
-                .flat_map(|mode_value| mode_value.clone().display_groups)
+                .flat_map(|(_, mode_value)| mode_value.clone().display_groups)
                 .flatten()
+                .map(|(group_name, group)| (group_name.to_lowercase(), group))
                 .collect();
-            let mut display_groups: IndexMap<String, Vec<KeyEvent>> = groups
-                .keys()
-                .into_iter()
-                .map(|key| (key.clone(), Vec::new()))
-                .collect();
+            let mut display_groups: IndexMap<String, Vec<KeyEvent>> =
+                groups.keys().map(|key| (key.clone(), Vec::new())).collect();
 
-            for mode_value in mode_values {
+            for (value_mode_name, mode_value) in mode_values {
                 for (key, key_binding) in mode_value.key_bindings {
-                    let events = parse_key_sequence(&key).unwrap();
+                    let key_path = format!("keybindings.{value_mode_name}.{key}");
+                    let events =
+                        parse_key_sequence(&key).map_err(|err| format!("{key_path}: {err}"))?;
+                    let action = Action::deserialize(
+                        StrDeserializer::<serde::de::value::Error>::new(&key_binding.action),
+                    )
+                    .map_err(|err| format!("{key_path}.action: {err}"))?;
+
+                    // A binding of the same keys replaces the earlier one including its key hint
+                    events_to_display_name.shift_remove(&events);
+                    for group_events in display_groups.values_mut() {
+                        group_events.retain(|event| !events.contains(event));
+                    }
+
                     if let Some(group) = key_binding.display_group {
                         display_groups
-                            .get_mut(&group)
-                            .unwrap()
+                            .get_mut(&group.to_lowercase())
+                            .ok_or_else(|| {
+                                format!("{key_path}.displayGroup: Unknown display group '{group}'")
+                            })?
                             .append(&mut events.clone());
                     } else if let Some(display_name) = key_binding.display_name {
                         events_to_display_name.insert(events.clone(), display_name);
                     }
 
-                    events_to_action.insert(events, key_binding.action.clone());
+                    events_to_action.insert(events, action);
                 }
             }
 
             for (group_name, key_events) in display_groups {
+                if key_events.is_empty() {
+                    continue;
+                }
                 events_to_display_name.insert(
                     key_events,
                     groups.get(&group_name).unwrap().display_name.clone(),
@@ -134,6 +295,16 @@ impl<'de> Deserialize<'de> for KeyBindings {
     }
 }
 
+impl<'de> Deserialize<'de> for KeyBindings {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let parsed_map = IndexMap::<String, ModeValue>::deserialize(deserializer)?;
+        KeyBindings::from_modes(parsed_map).map_err(de::Error::custom)
+    }
+}
+
 fn to_display_names(
     map: HashMap<Mode, IndexMap<Vec<KeyEvent>, String>>,
 ) -> HashMap<Mode, Vec<String>> {
@@ -206,42 +377,75 @@ fn to_display_names(
     display_names_map
 }
 
+// The aliases match the keys of the user's config files, which are lowercased by the config crate
 #[derive(Clone, Debug, Deserialize)]
 pub struct ModeValue {
-    #[serde(rename = "displayGroups")]
+    #[serde(rename = "displayGroups", alias = "displaygroups")]
     display_groups: Option<IndexMap<String, GroupValue>>,
-    extends: Option<Vec<Mode>>,
+    extends: Option<Vec<String>>,
     #[serde(flatten)]
     key_bindings: IndexMap<String, KeyBinding>,
 }
 
+impl ModeValue {
+    fn merge(&mut self, other: ModeValue) {
+        if other.extends.is_some() {
+            self.extends = other.extends;
+        }
+        if let Some(display_groups) = other.display_groups {
+            self.display_groups
+                .get_or_insert_with(IndexMap::new)
+                .extend(display_groups);
+        }
+        self.key_bindings.extend(other.key_bindings);
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct KeyBinding {
-    action: Action,
-    #[serde(rename = "displayName")]
+    action: String,
+    #[serde(rename = "displayName", alias = "displayname")]
     display_name: Option<String>,
-    #[serde(rename = "displayGroup")]
+    #[serde(rename = "displayGroup", alias = "displaygroup")]
     display_group: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct GroupValue {
-    #[serde(rename = "displayName")]
+    #[serde(rename = "displayName", alias = "displayname")]
     display_name: String,
 }
 
-fn collect_modes(modes: &IndexMap<Mode, ModeValue>, mode: &Mode) -> Vec<ModeValue> {
+/// Returns the values of the mode and of all modes it extends, together with the name of their mode.
+fn collect_modes(
+    modes: &IndexMap<String, ModeValue>,
+    mode: &str,
+    visited: &mut Vec<String>,
+) -> Result<Vec<(String, ModeValue)>, String> {
+    if visited.iter().any(|visited_mode| visited_mode == mode) {
+        return Err(format!(
+            "keybindings.{mode}.extends: Cyclic extension of mode '{mode}'"
+        ));
+    }
+    visited.push(mode.to_string());
+
     let mode_value = modes
         .get(mode)
-        .expect(&*format!("Mode {:?} not found", mode));
+        .ok_or_else(|| format!("keybindings.{mode}: Unknown mode '{mode}'"))?;
     let mut mode_values = Vec::new();
     if let Some(extended_modes) = &mode_value.extends {
         for extended_mode in extended_modes {
-            mode_values.append(collect_modes(modes, extended_mode).as_mut());
+            if !modes.contains_key(extended_mode) {
+                return Err(format!(
+                    "keybindings.{mode}.extends: Unknown mode '{extended_mode}'"
+                ));
+            }
+            mode_values.append(&mut collect_modes(modes, extended_mode, visited)?);
         }
     }
-    mode_values.push(mode_value.clone());
-    mode_values
+    visited.pop();
+    mode_values.push((mode.to_string(), mode_value.clone()));
+    Ok(mode_values)
 }
 
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
@@ -418,26 +622,45 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
 #[derive(Clone, Debug, Default, Deref, DerefMut)]
 pub struct Styles(pub HashMap<Mode, HashMap<String, Style>>);
 
+impl Styles {
+    fn from_modes(modes: IndexMap<String, HashMap<String, String>>) -> Result<Self, String> {
+        let mut styles = HashMap::new();
+        for (mode_name, inner_map) in modes {
+            let mode =
+                parse_mode(&mode_name).map_err(|err| format!("styles.{mode_name}: {err}"))?;
+            let mut converted_inner_map = HashMap::new();
+            for (name, style) in inner_map {
+                validate_style(&style)
+                    .map_err(|err| format!("styles.{mode_name}.{name}: {err}"))?;
+                converted_inner_map.insert(name.to_lowercase(), parse_style(&style));
+            }
+            styles.insert(mode, converted_inner_map);
+        }
+
+        Ok(Styles(styles))
+    }
+}
+
 impl<'de> Deserialize<'de> for Styles {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, String>>::deserialize(deserializer)?;
-
-        let styles = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(str, style)| (str, parse_style(&style)))
-                    .collect();
-                (mode, converted_inner_map)
-            })
-            .collect();
+        let parsed_map = IndexMap::<String, HashMap<String, String>>::deserialize(deserializer)?;
+        Styles::from_modes(parsed_map).map_err(de::Error::custom)
+    }
+}
 
-        Ok(Styles(styles))
+fn validate_style(line: &str) -> Result<(), String> {
+    let (foreground, background) =
+        line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
+    for color in [foreground, &background.replace("on ", "")] {
+        let (color, _) = process_color_string(color);
+        if !color.trim().is_empty() && parse_color(&color).is_none() {
+            return Err(format!("Unknown color '{}'", color.trim()));
+        }
     }
+    Ok(())
 }
 
 pub fn parse_style(line: &str) -> Style {
@@ -621,6 +844,90 @@ mod tests {
         Ok(())
     }
 
+    fn merge_user_config(user_config: &str) -> Result<Config, String> {
+        let default_config: RawConfig = json5::from_str(CONFIG).unwrap();
+        let user_config: RawConfig = json5::from_str(user_config).unwrap();
+        Config::merge(default_config, user_config)
+    }
+
+    #[test]
+    fn test_merge_user_keybindings() {
+        // The config crate lowercases all keys
+        let c = merge_user_config(
+            r#"{ keybindings: {
+                home: { "<q>": { action: "Suspend" } },
+                tab: { "<ctrl-r>": { action: "LoadAllItems", displayname: "Reload" } },
+            } }"#,
+        )
+        .unwrap();
+
+        let home = c.keybindings.bindings.get(&Mode::Home).unwrap();
+        assert_eq!(
+            home.get(&parse_key_sequence("<q>").unwrap()),
+            Some(&Action::Suspend)
+        );
+        assert!(!c
+            .keybindings
+            .display_names
+            .get(&Mode::Home)
+            .unwrap()
+            .contains(&"Quit [q]".to_string()));
+
+        let tab = c.keybindings.bindings.get(&Mode::ReadTab).unwrap();
+        assert_eq!(
+            tab.get(&parse_key_sequence("<Ctrl-r>").unwrap()),
+            Some(&Action::LoadAllItems)
+        );
+        assert_eq!(
+            tab.get(&parse_key_sequence("<tab>").unwrap()),
+            Some(&Action::NextTab)
+        );
+    }
+
+    #[test]
+    fn test_merge_invalid_keybinding() {
+        let unknown_action =
+            merge_user_config(r#"{ keybindings: { tab: { "<x>": { action: "Explode" } } } }"#);
+        let unknown_key =
+            merge_user_config(r#"{ keybindings: { tab: { "<ctrl-nope>": { action: "Quit" } } } }"#);
+        let unknown_mode =
+            merge_user_config(r#"{ keybindings: { nope: { "<x>": { action: "Quit" } } } }"#);
+
+        assert!(unknown_action
+            .unwrap_err()
+            .starts_with("keybindings.Tab.<x>.action:"));
+        assert!(unknown_key
+            .unwrap_err()
+            .starts_with("keybindings.Tab.<ctrl-nope>:"));
+        assert!(unknown_mode.unwrap_err().starts_with("keybindings.nope:"));
+    }
+
+    #[test]
+    fn test_merge_user_styles() {
+        let c = merge_user_config(
+            r#"{ theme: "high_contrast", styles: { home: { key_hint: "bold red on blue" } } }"#,
+        )
+        .unwrap();
+        let invalid_color = merge_user_config(r#"{ styles: { home: { error: "purple" } } }"#);
+        let invalid_name = merge_user_config(r#"{ styles: { home: { title: "red" } } }"#);
+        let invalid_mode = merge_user_config(r#"{ styles: { tab: { error: "red" } } }"#);
+
+        assert_eq!(c.theme.key_hint.bg, Some(Color::Indexed(4)));
+        assert_eq!(c.theme.accent, Theme::high_contrast().accent);
+        assert_eq!(
+            invalid_color.unwrap_err(),
+            "styles.Home.error: Unknown color 'purple'"
+        );
+        assert_eq!(
+            invalid_name.unwrap_err(),
+            "styles.Home: Unknown style 'title'"
+        );
+        assert_eq!(
+            invalid_mode.unwrap_err(),
+            "styles.Tab: Styles can only be configured for the mode 'Home'"
+        );
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
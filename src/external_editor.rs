@@ -0,0 +1,101 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+
+/// Opens the text in the editor of `$VISUAL` or `$EDITOR`, or `vi` if neither is set,
+/// and returns the edited text. The terminal must not be in raw mode while the editor runs.
+pub fn edit(text: &str) -> Result<String, String> {
+    let command = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    edit_with(&command, text)
+}
+
+/// The start of the comment that [with_error_comment] puts above invalid text
+const ERROR_COMMENT: &str = "// Invalid JSON: ";
+
+/// Puts the validation error in a comment above the text, so it can be seen in the editor.
+/// The position of the error is adjusted to the comment line.
+pub fn with_error_comment(text: &str, error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    let message = message.strip_suffix(&position).unwrap_or(&message);
+    format!(
+        "{ERROR_COMMENT}{message} at line {} column {}\n{text}",
+        error.line() + 1,
+        error.column()
+    )
+}
+
+/// Removes the comment of [with_error_comment] if it was not removed in the editor.
+pub fn strip_error_comment(text: &str) -> &str {
+    if text.starts_with(ERROR_COMMENT) {
+        text.split_once('\n').map_or("", |(_, text)| text)
+    } else {
+        text
+    }
+}
+
+/// Opens the text in a temporary JSON file with the command, which may contain arguments, e.g. `code --wait`.
+fn edit_with(command: &str, text: &str) -> Result<String, String> {
+    let mut args = command.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| "No external editor configured".to_string())?;
+
+    // Created exclusively and only readable by the user, removed when the path is dropped
+    let mut file = tempfile::Builder::new()
+        .prefix("hmq-")
+        .suffix(".json")
+        .tempfile()
+        .map_err(|err| format!("Failed to create a temporary file: {err}"))?;
+    file.write_all(text.as_bytes())
+        .map_err(|err| format!("Failed to write {}: {err}", file.path().display()))?;
+    let path = file.into_temp_path();
+
+    let status = Command::new(program).args(args).arg(&path).status();
+    match status {
+        Ok(status) if status.success() => fs::read_to_string(&path)
+            .map(|edited| edited.trim_end_matches('\n').to_string())
+            .map_err(|err| format!("Failed to read {}: {err}", path.display())),
+        Ok(status) => Err(format!("'{command}' exited with {status}")),
+        Err(err) => Err(format!("Failed to start '{command}': {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::external_editor::{edit_with, strip_error_comment, with_error_comment};
+
+    #[test]
+    fn test_edit_with() {
+        let edited = edit_with("sed -i s/old/new/", "{\n  \"id\": \"old\"\n}");
+
+        assert_eq!(Ok("{\n  \"id\": \"new\"\n}".to_string()), edited);
+    }
+
+    #[test]
+    fn test_edit_with_failing_editor() {
+        let edited = edit_with("false", "{}");
+
+        assert!(edited.unwrap_err().starts_with("'false' exited with"));
+    }
+
+    #[test]
+    fn test_error_comment() {
+        let text = "{\n  \"id\": \"old\"\n";
+        let error = serde_json::from_str::<serde_json::Value>(text).unwrap_err();
+
+        let commented = with_error_comment(text, &error);
+
+        assert_eq!(
+            "// Invalid JSON: EOF while parsing an object at line 4 column 0\n{\n  \"id\": \"old\"\n",
+            commented
+        );
+        assert_eq!(text, strip_error_comment(&commented));
+        assert_eq!(text, strip_error_comment(text));
+    }
+}
@@ -0,0 +1,656 @@
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use hivemq_openapi::models::backup::State;
+use hivemq_openapi::models::{
+    Backup, BehaviorPolicy, ClientDetails, DataPolicy, DiagnosticArchive, Schema, Script,
+    TraceRecording,
+};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+
+use crate::cli::{
+    BackupsCommand, ClientsCommand, Command, DataHubCommand, DataHubItemCommand,
+    DiagnosticArchivesCommand, OutputFormat, PolicyCommand, TraceRecordingsCommand,
+};
+use crate::config::ProfileConfig;
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::repository::Repository;
+use crate::services::backups_service::BackupService;
+use crate::services::behavior_policy_service::BehaviorPolicyService;
+use crate::services::client_details_service::{ClientDetailsService, ClientSubscriptions};
+use crate::services::data_hub_export_service::DataHubExportService;
+use crate::services::data_hub_service::DataHubService;
+use crate::services::data_hub_sync_service::{DataHubSyncService, Plan};
+use crate::services::data_policy_service::DataPolicyService;
+use crate::services::diagnostic_archive_service::DiagnosticArchiveService;
+use crate::services::schema_service::SchemaService;
+use crate::services::scripts_service::ScriptService;
+use crate::services::trace_recordings_service::TraceRecordingService;
+use crate::sqlite::init_sqlite;
+
+/// The interval in which a backup is polled while waiting for it to finish.
+const BACKUP_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// The time after which waiting for a backup or restore fails.
+const BACKUP_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Executes a command without the TUI and returns its formatted output.
+pub async fn run_command(
+    command: Command,
+    profile: &ProfileConfig,
+    client_details_concurrency: usize,
+    output: OutputFormat,
+) -> Result<String, String> {
+    if profile.read_only && command.is_mutating() {
+        return Err(format!(
+            "Changes are not allowed with the profile '{}'",
+            profile.name
+        ));
+    }
+
+    let runner = CommandRunner {
+        connection: profile.connection()?,
+        sqlite_pool: init_sqlite(),
+        client_details_concurrency,
+        output,
+    };
+    match command {
+        Command::Clients { command } => runner.clients(command).await,
+        Command::DataHub { command } => runner.data_hub(command).await,
+        Command::Schemas { command } => runner.schemas(command).await,
+        Command::Scripts { command } => runner.scripts(command).await,
+        Command::DataPolicies { command } => runner.data_policies(command).await,
+        Command::BehaviorPolicies { command } => runner.behavior_policies(command).await,
+        Command::TraceRecordings { command } => runner.trace_recordings(command).await,
+        Command::Backups { command } => runner.backups(command).await,
+        Command::DiagnosticArchives { command } => runner.diagnostic_archives(command).await,
+    }
+}
+
+struct CommandRunner {
+    connection: HivemqConnection,
+    sqlite_pool: Pool<SqliteConnectionManager>,
+    client_details_concurrency: usize,
+    output: OutputFormat,
+}
+
+impl CommandRunner {
+    fn repository<T: Serialize + DeserializeOwned>(
+        &self,
+        table: &str,
+        id_fn: fn(&T) -> String,
+        order_by: &str,
+    ) -> Result<Arc<Repository<T>>, String> {
+        let repository = Repository::<T>::init(&self.sqlite_pool, table, id_fn, order_by)
+            .map_err(|err| format!("{err:?}"))?;
+        Ok(Arc::new(repository))
+    }
+
+    async fn clients(&self, command: ClientsCommand) -> Result<String, String> {
+        let repository = self.repository::<ClientDetails>(
+            "client_details",
+            |val| val.id.clone().unwrap(),
+            "connectedAt",
+        )?;
+        let subscription_repository = self.repository::<ClientSubscriptions>(
+            "client_subscriptions",
+            |val| val.client_id.clone(),
+            "clientId",
+        )?;
+        let service = ClientDetailsService::new(
+            repository.clone(),
+            subscription_repository,
+            &self.connection,
+        );
+
+        match command {
+            ClientsCommand::List => {
+                let errors = service
+                    .load_details(
+                        self.client_details_concurrency,
+                        CancellationToken::new(),
+                        |_, _, _| {},
+                    )
+                    .await?;
+                for message in errors {
+                    eprintln!("Skipped: {message}");
+                }
+                self.format_list(
+                    &find_all(&repository)?,
+                    &["id", "connected", "connectedAt", "sessionExpiryInterval"],
+                )
+            }
+            ClientsCommand::Get { client_id } => {
+                self.format_item(&service.load_client_details(&client_id).await?)
+            }
+        }
+    }
+
+    async fn data_hub(&self, command: DataHubCommand) -> Result<String, String> {
+        let service = DataHubService::new(&self.connection);
+
+        match command {
+            DataHubCommand::StartTrial => {
+                service.start_trial_mode().await?;
+                Ok("Started the Data Hub trial mode".to_string())
+            }
+            DataHubCommand::Export { directory } => {
+                let summary = DataHubExportService::new(&self.connection)
+                    .export(&directory)
+                    .await?;
+                self.format_item(&summary)
+            }
+            DataHubCommand::Plan { directory } => {
+                let plan = DataHubSyncService::new(&self.connection)
+                    .plan(&directory)
+                    .await?;
+                self.format_plan(&plan)
+            }
+            DataHubCommand::Apply { directory } => {
+                let service = DataHubSyncService::new(&self.connection);
+                let plan = service.plan(&directory).await?;
+                service.apply(&plan).await?;
+                self.format_plan(&plan)
+            }
+        }
+    }
+
+    async fn schemas(&self, command: DataHubItemCommand) -> Result<String, String> {
+        let repository = self.repository::<Schema>("schemas", |val| val.id.clone(), "createdAt")?;
+        let service = SchemaService::new(repository.clone(), &self.connection);
+
+        match command {
+            DataHubItemCommand::List => {
+                service.load_schemas().await?;
+                self.format_list(
+                    &find_all(&repository)?,
+                    &["id", "type", "version", "createdAt"],
+                )
+            }
+            DataHubItemCommand::Get { id } => {
+                service.load_schemas().await?;
+                self.format_item(&find_by_id(&repository, "Schema", &id)?)
+            }
+            DataHubItemCommand::Create { file } => {
+                let id = service.create_schema(&read_file(&file)?).await?;
+                self.format_item(&find_by_id(&repository, "Schema", &id)?)
+            }
+            DataHubItemCommand::Delete { id } => {
+                service.delete_schema(&id).await?;
+                Ok(format!("Deleted schema '{id}'"))
+            }
+        }
+    }
+
+    async fn scripts(&self, command: DataHubItemCommand) -> Result<String, String> {
+        let repository = self.repository::<Script>("scripts", |val| val.id.clone(), "createdAt")?;
+        let service = ScriptService::new(repository.clone(), &self.connection);
+
+        match command {
+            DataHubItemCommand::List => {
+                service.load_scripts().await?;
+                self.format_list(
+                    &find_all(&repository)?,
+                    &["id", "functionType", "version", "createdAt"],
+                )
+            }
+            DataHubItemCommand::Get { id } => {
+                service.load_scripts().await?;
+                self.format_item(&find_by_id(&repository, "Script", &id)?)
+            }
+            DataHubItemCommand::Create { file } => {
+                let id = service.create_script(&read_file(&file)?).await?;
+                self.format_item(&find_by_id(&repository, "Script", &id)?)
+            }
+            DataHubItemCommand::Delete { id } => {
+                service.delete_script(&id).await?;
+                Ok(format!("Deleted script '{id}'"))
+            }
+        }
+    }
+
+    async fn data_policies(&self, command: PolicyCommand) -> Result<String, String> {
+        let repository =
+            self.repository::<DataPolicy>("data_policies", |val| val.id.clone(), "lastUpdatedAt")?;
+        let service = DataPolicyService::new(repository.clone(), &self.connection);
+
+        match command {
+            PolicyCommand::List => {
+                service.load_data_policies().await?;
+                self.format_list(
+                    &find_all(&repository)?,
+                    &["id", "matching.topicFilter", "lastUpdatedAt"],
+                )
+            }
+            PolicyCommand::Get { id } => {
+                service.load_data_policies().await?;
+                self.format_item(&find_by_id(&repository, "Data policy", &id)?)
+            }
+            PolicyCommand::Apply { file } => {
+                let data_policy = read_file(&file)?;
+                let id = get_id(&data_policy)?;
+                service.load_data_policies().await?;
+                if repository.find_by_id(&id).is_ok() {
+                    service.update_data_policy(&data_policy).await?;
+                } else {
+                    service.create_data_policy(&data_policy).await?;
+                }
+                self.format_item(&find_by_id(&repository, "Data policy", &id)?)
+            }
+            PolicyCommand::Delete { id } => {
+                service.delete_data_policy(&id).await?;
+                Ok(format!("Deleted data policy '{id}'"))
+            }
+        }
+    }
+
+    async fn behavior_policies(&self, command: PolicyCommand) -> Result<String, String> {
+        let repository = self.repository::<BehaviorPolicy>(
+            "behavior_policies",
+            |val| val.id.clone(),
+            "lastUpdatedAt",
+        )?;
+        let service = BehaviorPolicyService::new(repository.clone(), &self.connection);
+
+        match command {
+            PolicyCommand::List => {
+                service.load_behavior_policies().await?;
+                self.format_list(
+                    &find_all(&repository)?,
+                    &["id", "behavior.id", "lastUpdatedAt"],
+                )
+            }
+            PolicyCommand::Get { id } => {
+                service.load_behavior_policies().await?;
+                self.format_item(&find_by_id(&repository, "Behavior policy", &id)?)
+            }
+            PolicyCommand::Apply { file } => {
+                let behavior_policy = read_file(&file)?;
+                let id = get_id(&behavior_policy)?;
+                service.load_behavior_policies().await?;
+                if repository.find_by_id(&id).is_ok() {
+                    service.update_behavior_policy(&behavior_policy).await?;
+                } else {
+                    service.create_behavior_policy(&behavior_policy).await?;
+                }
+                self.format_item(&find_by_id(&repository, "Behavior policy", &id)?)
+            }
+            PolicyCommand::Delete { id } => {
+                service.delete_behavior_policy(&id).await?;
+                Ok(format!("Deleted behavior policy '{id}'"))
+            }
+        }
+    }
+
+    async fn trace_recordings(&self, command: TraceRecordingsCommand) -> Result<String, String> {
+        let repository = self.repository::<TraceRecording>(
+            "trace_recordings",
+            |val| val.name.clone().unwrap(),
+            "startAt",
+        )?;
+        let service = TraceRecordingService::new(repository.clone(), &self.connection);
+
+        match command {
+            TraceRecordingsCommand::List => {
+                service.load_trace_recordings().await?;
+                self.format_list(
+                    &find_all(&repository)?,
+                    &["name", "state", "startAt", "endAt"],
+                )
+            }
+            TraceRecordingsCommand::Get { name } => {
+                service.load_trace_recordings().await?;
+                self.format_item(&find_by_id(&repository, "Trace recording", &name)?)
+            }
+            TraceRecordingsCommand::Create { file } => {
+                let name = service.create_trace_recording(&read_file(&file)?).await?;
+                self.format_item(&find_by_id(&repository, "Trace recording", &name)?)
+            }
+            TraceRecordingsCommand::Stop { name } => {
+                service.stop_trace_recording(&name).await?;
+                self.format_item(&find_by_id(&repository, "Trace recording", &name)?)
+            }
+            TraceRecordingsCommand::Delete { name } => {
+                service.delete_trace_recording(&name).await?;
+                Ok(format!("Deleted trace recording '{name}'"))
+            }
+        }
+    }
+
+    async fn backups(&self, command: BackupsCommand) -> Result<String, String> {
+        let repository =
+            self.repository::<Backup>("backups", |val| val.id.clone().unwrap(), "createdAt")?;
+        let service = BackupService::new(repository.clone(), &self.connection);
+
+        let (backup_id, wait) = match command {
+            BackupsCommand::List => {
+                service.load_backups().await?;
+                return self.format_list(
+                    &find_all(&repository)?,
+                    &["id", "state", "createdAt", "bytes"],
+                );
+            }
+            BackupsCommand::Create { wait } => (service.start_backup().await?, wait),
+            BackupsCommand::Restore { id, wait } => (service.restore_backup(&id).await?, wait),
+        };
+
+        if !wait {
+            return self.format_item(&find_by_id(&repository, "Backup", &backup_id)?);
+        }
+
+        let backup = service
+            .wait_for_backup(
+                &backup_id,
+                BACKUP_POLL_INTERVAL,
+                BACKUP_TIMEOUT,
+                CancellationToken::new(),
+            )
+            .await?;
+        match backup.state {
+            Some(State::Failed) | Some(State::RestoreFailed) => Err(format!(
+                "Backup '{backup_id}' failed: {}",
+                backup.fail_reason.flatten().unwrap_or_default()
+            )),
+            _ => self.format_item(&backup),
+        }
+    }
+
+    async fn diagnostic_archives(
+        &self,
+        command: DiagnosticArchivesCommand,
+    ) -> Result<String, String> {
+        let repository = self.repository::<DiagnosticArchive>(
+            "diagnostic_archives",
+            |val| val.id.clone().unwrap(),
+            "createdAt",
+        )?;
+        let service = DiagnosticArchiveService::new(repository.clone(), &self.connection);
+
+        match command {
+            DiagnosticArchivesCommand::Create => {
+                let id = service.create_diagnostic_archive().await?;
+                self.format_item(&find_by_id(&repository, "Diagnostic archive", &id)?)
+            }
+        }
+    }
+
+    fn format_list<T: Serialize>(&self, items: &[T], columns: &[&str]) -> Result<String, String> {
+        match self.output {
+            OutputFormat::Table => {
+                let values = to_values(items)?;
+                let rows = values
+                    .iter()
+                    .map(|value| {
+                        columns
+                            .iter()
+                            .map(|column| format_cell(value.pointer(&to_pointer(column))))
+                            .collect()
+                    })
+                    .collect();
+                Ok(format_table(columns, rows))
+            }
+            _ => self.format_item(&items),
+        }
+    }
+
+    fn format_plan(&self, plan: &Plan) -> Result<String, String> {
+        match self.output {
+            OutputFormat::Table if plan.is_empty() => Ok("No changes".to_string()),
+            OutputFormat::Table => self.format_list(&plan.changes, &["change", "type", "id"]),
+            _ => self.format_item(plan),
+        }
+    }
+
+    fn format_item<T: Serialize>(&self, item: &T) -> Result<String, String> {
+        match self.output {
+            OutputFormat::Table => {
+                let value = serde_json::to_value(item).map_err(|err| err.to_string())?;
+                let rows = match value {
+                    Value::Object(fields) => fields
+                        .iter()
+                        .map(|(key, value)| vec![key.clone(), format_cell(Some(value))])
+                        .collect(),
+                    value => vec![vec![format_cell(Some(&value))]],
+                };
+                Ok(format_table(&["field", "value"], rows))
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(item).map_err(|err| err.to_string()),
+            OutputFormat::Yaml => serde_yaml::to_string(item)
+                .map(|yaml| yaml.trim_end().to_string())
+                .map_err(|err| err.to_string()),
+        }
+    }
+}
+
+fn find_all<T: Serialize + DeserializeOwned>(repository: &Repository<T>) -> Result<Vec<T>, String> {
+    repository.find_all().map_err(|err| format!("{err:?}"))
+}
+
+fn find_by_id<T: Serialize + DeserializeOwned>(
+    repository: &Repository<T>,
+    item_name: &str,
+    id: &str,
+) -> Result<T, String> {
+    repository
+        .find_by_id(id)
+        .map_err(|_| format!("{item_name} '{id}' not found"))
+}
+
+/// Reads the file or stdin if the path is `-`.
+fn read_file(path: &Path) -> Result<String, String> {
+    if path == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|err| format!("Reading stdin failed: {err}"))?;
+        Ok(content)
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|err| format!("Reading {} failed: {err}", path.display()))
+    }
+}
+
+fn get_id(json: &str) -> Result<String, String> {
+    let value: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    value
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "The policy has no id".to_string())
+}
+
+fn to_values<T: Serialize>(items: &[T]) -> Result<Vec<Value>, String> {
+    items
+        .iter()
+        .map(|item| serde_json::to_value(item).map_err(|err| err.to_string()))
+        .collect()
+}
+
+/// Converts a dotted column like `matching.topicFilter` to a JSON pointer.
+fn to_pointer(column: &str) -> String {
+    format!("/{}", column.replace('.', "/"))
+}
+
+fn format_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(string)) => string.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn format_table(header: &[&str], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|column| column.len()).collect();
+    for row in &rows {
+        for (index, cell) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(index) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let header = header.iter().map(|column| column.to_uppercase()).collect();
+    std::iter::once(header)
+        .chain(rows)
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use hivemq_openapi::models::{
+        DataPolicy, DataPolicyList, PaginationCursor, Schema, SchemaList,
+    };
+    use httpmock::Method::{GET, POST, PUT};
+    use httpmock::MockServer;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::{Command, DataHubItemCommand, OutputFormat, PolicyCommand};
+    use crate::config::ProfileConfig;
+    use crate::headless::{format_table, run_command};
+
+    fn profile(broker: &MockServer, read_only: bool) -> ProfileConfig {
+        ProfileConfig {
+            name: "test".to_string(),
+            host: broker.base_url(),
+            auth: Default::default(),
+            tls: Default::default(),
+            read_only,
+        }
+    }
+
+    fn build_schema(id: &str) -> Schema {
+        let mut schema = Schema::new(id.to_string(), "e30=".to_string(), "JSON".to_string());
+        schema.version = Some(1);
+        schema.created_at = Some("2024-01-01T00:00:00Z".to_string());
+        schema
+    }
+
+    #[test]
+    fn test_format_table() {
+        let table = format_table(
+            &["id", "state"],
+            vec![
+                vec!["backup-1".to_string(), "COMPLETED".to_string()],
+                vec!["b2".to_string(), String::new()],
+            ],
+        );
+
+        assert_eq!(
+            indoc! {"
+                ID        STATE
+                backup-1  COMPLETED
+                b2"},
+            table
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_schemas() {
+        let broker = MockServer::start();
+        let response = SchemaList {
+            _links: Some(Some(Box::new(PaginationCursor::new()))),
+            items: Some(vec![build_schema("schema-1"), build_schema("schema-2")]),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/data-hub/schemas");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&response).unwrap());
+        });
+        let command = Command::Schemas {
+            command: DataHubItemCommand::List,
+        };
+
+        let table = run_command(command, &profile(&broker, false), 1, OutputFormat::Table)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            indoc! {"
+                ID        TYPE  VERSION  CREATEDAT
+                schema-1  JSON  1        2024-01-01T00:00:00Z
+                schema-2  JSON  1        2024-01-01T00:00:00Z"},
+            table
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_existing_data_policy() {
+        let broker = MockServer::start();
+        let data_policy = DataPolicy::new(
+            "policy-1".to_string(),
+            hivemq_openapi::models::DataPolicyMatching::new("topic/#".to_string()),
+        );
+        let response = DataPolicyList {
+            _links: None,
+            items: Some(vec![data_policy.clone()]),
+        };
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v1/data-hub/data-validation/policies");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&response).unwrap());
+        });
+        let create_mock = broker.mock(|when, then| {
+            when.method(POST);
+            then.status(201);
+        });
+        let update_mock = broker.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/data-hub/data-validation/policies/policy-1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&data_policy).unwrap());
+        });
+        let tempdir = tempfile::tempdir().unwrap();
+        let file = tempdir.path().join("data-policy.json");
+        std::fs::write(&file, serde_json::to_string(&data_policy).unwrap()).unwrap();
+        let command = Command::DataPolicies {
+            command: PolicyCommand::Apply { file },
+        };
+
+        let json = run_command(command, &profile(&broker, false), 1, OutputFormat::Json)
+            .await
+            .unwrap();
+
+        update_mock.assert();
+        create_mock.assert_hits(0);
+        assert_eq!(serde_json::to_string_pretty(&data_policy).unwrap(), json);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_profile() {
+        let broker = MockServer::start();
+        let delete_mock = broker.mock(|_, then| {
+            then.status(204);
+        });
+        let command = Command::Schemas {
+            command: DataHubItemCommand::Delete {
+                id: "schema-1".to_string(),
+            },
+        };
+
+        let result = run_command(command, &profile(&broker, true), 1, OutputFormat::Table).await;
+
+        assert!(result.is_err());
+        delete_mock.assert_hits(0);
+    }
+}
@@ -1,8 +1,18 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
 use hivemq_openapi::apis::Error;
 use hivemq_openapi::{apis::configuration::Configuration, models::PaginationCursor};
 use lazy_static::lazy_static;
 use regex::Regex;
-use serde::Serialize;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::{Certificate, Identity};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+/// The minimum interval between two progress reports of a download.
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
 
 pub fn get_cursor(links: Option<Option<Box<PaginationCursor>>>) -> Option<String> {
     lazy_static! {
@@ -18,10 +28,133 @@ pub fn get_cursor(links: Option<Option<Box<PaginationCursor>>>) -> Option<String
         .map(|mat| mat.as_str().to_string())
 }
 
-pub fn build_rest_api_config(host: String) -> Configuration {
-    let mut configuration = Configuration::default();
-    configuration.base_path = host.to_string();
-    configuration
+/// How the HiveMQ REST API is reached.
+#[derive(Clone, Debug)]
+pub struct HivemqConnection {
+    pub host: String,
+    pub authentication: Option<Authentication>,
+    pub tls: TlsConfig,
+    /// The HTTP client that is shared by all services of the connection.
+    pub client: reqwest::Client,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Authentication {
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    Bearer(String),
+}
+
+/// The TLS settings of the connection to the REST API.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct TlsConfig {
+    /// A PEM file with additional CA certificates that are trusted.
+    pub ca_cert: Option<PathBuf>,
+    /// A PEM file with the client certificate for mutual TLS.
+    pub client_cert: Option<PathBuf>,
+    /// A PEM file with the PKCS#8 private key of the client certificate.
+    pub client_key: Option<PathBuf>,
+    /// Skips the verification of the server certificate.
+    #[serde(default)]
+    pub insecure: bool,
+}
+
+impl HivemqConnection {
+    pub fn new(host: String) -> Self {
+        HivemqConnection {
+            host,
+            authentication: None,
+            tls: TlsConfig::default(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Creates the connection and builds its HTTP client once.
+    pub fn connect(
+        host: String,
+        authentication: Option<Authentication>,
+        tls: TlsConfig,
+    ) -> Result<Self, String> {
+        let mut connection = HivemqConnection {
+            authentication,
+            tls,
+            ..HivemqConnection::new(host)
+        };
+        connection.client = build_http_client(&connection)?;
+        Ok(connection)
+    }
+}
+
+impl Authentication {
+    fn header_value(&self) -> String {
+        match self {
+            Authentication::Basic { username, password } => {
+                let credentials = format!("{username}:{}", password.as_deref().unwrap_or_default());
+                format!("Basic {}", BASE64_STANDARD.encode(credentials))
+            }
+            Authentication::Bearer(token) => format!("Bearer {token}"),
+        }
+    }
+}
+
+/// Builds the HTTP client with the authentication and TLS settings of the connection.
+pub fn build_http_client(connection: &HivemqConnection) -> Result<reqwest::Client, String> {
+    let mut headers = HeaderMap::new();
+    if let Some(authentication) = &connection.authentication {
+        let mut value = HeaderValue::from_str(&authentication.header_value())
+            .map_err(|err| format!("Invalid authentication: {err}"))?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    let mut builder = reqwest::Client::builder().default_headers(headers);
+    let tls = &connection.tls;
+    if let Some(ca_cert) = &tls.ca_cert {
+        let certificates = Certificate::from_pem_bundle(&read_file(ca_cert)?)
+            .map_err(|err| format!("Invalid CA certificate {}: {err}", ca_cert.display()))?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    match (&tls.client_cert, &tls.client_key) {
+        (Some(client_cert), Some(client_key)) => {
+            let identity =
+                Identity::from_pkcs8_pem(&read_file(client_cert)?, &read_file(client_key)?)
+                    .map_err(|err| format!("Invalid client certificate or key: {err}"))?;
+            builder = builder.identity(identity);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(
+                "A client certificate and a client key are both required for mutual TLS"
+                    .to_string(),
+            )
+        }
+    }
+    if tls.insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder
+        .build()
+        .map_err(|err| format!("Can not build HTTP client: {err}"))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("Can not read {}: {err}", path.display()))
+}
+
+/// The authentication is sent as a default header of the HTTP client and not through
+/// `basic_auth` or `bearer_access_token` of the configuration. The generated API functions
+/// never read those fields and the downloads do not use the generated functions at all.
+pub fn build_rest_api_config(connection: &HivemqConnection) -> Configuration {
+    Configuration {
+        base_path: connection.host.clone(),
+        client: connection.client.clone(),
+        ..Configuration::default()
+    }
 }
 
 pub fn transform_api_err<T: Serialize>(error: Error<T>) -> String {
@@ -37,12 +170,245 @@ pub fn transform_api_err<T: Serialize>(error: Error<T>) -> String {
     format!("API request failed: {}", message)
 }
 
+/// Streams the file at the given path of the REST API into the directory and returns its path.
+/// The file name is taken from the Content-Disposition header and falls back to `fallback_file_name`.
+/// Existing files are kept, the file name gets a counter like `name (1).ext` instead.
+///
+/// The generated `download_*_file` functions of the API can not be used for this,
+/// they read the whole body as text and parse it as JSON, which fails for binary files.
+/// `on_progress` is called with the downloaded and the total bytes, if the total is known.
+pub async fn download_file<F: FnMut(u64, Option<u64>)>(
+    config: &Configuration,
+    path: &str,
+    directory: &Path,
+    fallback_file_name: &str,
+    mut on_progress: F,
+) -> Result<PathBuf, String> {
+    let url = format!("{}{}", config.base_path, path);
+    let mut response = config
+        .client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| format!("API request failed: {err}"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let content = response.text().await.unwrap_or_default();
+        return Err(format!("API request failed: {status} {content}"));
+    }
+
+    let file_name = response
+        .headers()
+        .get("content-disposition")
+        .and_then(|value| value.to_str().ok())
+        .and_then(get_file_name)
+        .unwrap_or_else(|| fallback_file_name.to_string());
+
+    tokio::fs::create_dir_all(directory)
+        .await
+        .map_err(|err| format!("Can not create directory {}: {err}", directory.display()))?;
+    let (mut file, file_path) = create_new_file(directory, &file_name).await?;
+
+    let total = response.content_length();
+    let mut downloaded = 0;
+    let mut last_progress = Instant::now();
+    on_progress(downloaded, total);
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(err) => {
+                let _ = tokio::fs::remove_file(&file_path).await;
+                return Err(format!("Download failed: {err}"));
+            }
+        };
+        if let Err(err) = file.write_all(&chunk).await {
+            let _ = tokio::fs::remove_file(&file_path).await;
+            return Err(format!("Can not write file {}: {err}", file_path.display()));
+        }
+
+        downloaded += chunk.len() as u64;
+        if last_progress.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL {
+            on_progress(downloaded, total);
+            last_progress = Instant::now();
+        }
+    }
+    file.flush()
+        .await
+        .map_err(|err| format!("Can not write file {}: {err}", file_path.display()))?;
+    on_progress(downloaded, total);
+
+    Ok(file_path)
+}
+
+/// Creates a new file with the name in the directory.
+/// If the file already exists, a counter is added before the full extension until the file
+/// does not exist yet, e.g. `backup (1).tar.gz`.
+async fn create_new_file(
+    directory: &Path,
+    file_name: &str,
+) -> Result<(tokio::fs::File, PathBuf), String> {
+    // A leading dot belongs to the name of hidden files and does not start the extension.
+    let (stem, extension) = match file_name.get(1..).and_then(|rest| rest.find('.')) {
+        Some(index) => file_name.split_at(index + 1),
+        None => (file_name, ""),
+    };
+
+    let mut file_path = directory.join(file_name);
+    for counter in 1.. {
+        match tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+            .await
+        {
+            Ok(file) => return Ok((file, file_path)),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                file_path = directory.join(format!("{stem} ({counter}){extension}"));
+            }
+            Err(err) => {
+                return Err(format!(
+                    "Can not create file {}: {err}",
+                    file_path.display()
+                ))
+            }
+        }
+    }
+    unreachable!("No free file name for {file_name}")
+}
+
+/// Extracts the file name of a Content-Disposition header value.
+/// Directories in the file name are ignored, so the file can not be written outside the target directory.
+fn get_file_name(content_disposition: &str) -> Option<String> {
+    lazy_static! {
+        static ref FILE_NAME_REGEX: Regex = Regex::new(r#"filename="?([^";]+)"?"#).unwrap();
+    }
+
+    let file_name = FILE_NAME_REGEX
+        .captures(content_disposition)?
+        .get(1)?
+        .as_str()
+        .trim();
+    Path::new(file_name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use hivemq_openapi::models::PaginationCursor;
     use httpmock::{Method::GET, Mock, MockServer};
     use serde::Serialize;
 
+    use std::path::PathBuf;
+
+    use crate::hivemq_rest_client::{
+        build_http_client, build_rest_api_config, create_new_file, Authentication,
+        HivemqConnection, TlsConfig,
+    };
+
+    async fn assert_authorization_header(authentication: Authentication, expected: &str) {
+        let broker = MockServer::start();
+        let mock = broker.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v1/mqtt/clients")
+                .header("authorization", expected);
+            then.status(200);
+        });
+        let connection = HivemqConnection::connect(
+            broker.base_url(),
+            Some(authentication),
+            TlsConfig::default(),
+        )
+        .unwrap();
+        let config = build_rest_api_config(&connection);
+
+        config
+            .client
+            .get(format!("{}/api/v1/mqtt/clients", config.base_path))
+            .send()
+            .await
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_basic_authentication() {
+        let authentication = Authentication::Basic {
+            username: "user".to_string(),
+            password: Some("secret".to_string()),
+        };
+        assert_authorization_header(authentication, "Basic dXNlcjpzZWNyZXQ=").await;
+    }
+
+    #[tokio::test]
+    async fn test_bearer_authentication() {
+        let authentication = Authentication::Bearer("token".to_string());
+        assert_authorization_header(authentication, "Bearer token").await;
+    }
+
+    #[test]
+    fn test_tls_missing_ca_cert() {
+        let connection = HivemqConnection {
+            tls: TlsConfig {
+                ca_cert: Some(PathBuf::from("/does/not/exist.pem")),
+                ..TlsConfig::default()
+            },
+            ..HivemqConnection::new("https://localhost:8888".to_string())
+        };
+
+        let result = build_http_client(&connection);
+
+        assert!(result.unwrap_err().contains("/does/not/exist.pem"));
+    }
+
+    #[test]
+    fn test_tls_client_cert_without_key() {
+        let connection = HivemqConnection {
+            tls: TlsConfig {
+                client_cert: Some(PathBuf::from("client.pem")),
+                ..TlsConfig::default()
+            },
+            ..HivemqConnection::new("https://localhost:8888".to_string())
+        };
+
+        assert!(build_http_client(&connection).is_err());
+    }
+
+    #[test]
+    fn test_tls_insecure() {
+        let connection = HivemqConnection {
+            tls: TlsConfig {
+                insecure: true,
+                ..TlsConfig::default()
+            },
+            ..HivemqConnection::new("https://localhost:8888".to_string())
+        };
+
+        assert!(build_http_client(&connection).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_new_file_keeps_full_extension() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let (_, first) = create_new_file(directory.path(), "backup.tar.gz")
+            .await
+            .unwrap();
+        let (_, second) = create_new_file(directory.path(), "backup.tar.gz")
+            .await
+            .unwrap();
+        let (_, hidden) = create_new_file(directory.path(), ".backup").await.unwrap();
+        let (_, second_hidden) = create_new_file(directory.path(), ".backup").await.unwrap();
+
+        assert_eq!(directory.path().join("backup.tar.gz"), first);
+        assert_eq!(directory.path().join("backup (1).tar.gz"), second);
+        assert_eq!(directory.path().join(".backup"), hidden);
+        assert_eq!(directory.path().join(".backup (1)"), second_hidden);
+    }
+
     pub fn create_responses<T>(
         url: &str,
         build_list: fn(usize, usize, Option<Option<Box<PaginationCursor>>>) -> T,
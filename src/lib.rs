@@ -3,10 +3,14 @@ pub mod app;
 pub mod cli;
 pub mod components;
 pub mod config;
+pub mod external_editor;
+pub mod headless;
 pub mod hivemq_rest_client;
 pub mod mode;
+pub mod references;
 pub mod repository;
 pub mod services;
 pub mod sqlite;
+pub mod theme;
 pub mod tui;
 pub mod utils;
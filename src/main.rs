@@ -3,10 +3,13 @@
 #![allow(unused_variables)]
 
 use clap::Parser;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use hmq_tui::app::App;
 use hmq_tui::cli::Cli;
-use hmq_tui::utils::{initialize_logging, initialize_panic_handler};
+use hmq_tui::config::Config;
+use hmq_tui::headless::run_command;
+use hmq_tui::theme::init_theme;
+use hmq_tui::utils::{get_data_dir, initialize_logging, initialize_panic_handler};
 
 async fn tokio_main() -> Result<()> {
     initialize_logging()?;
@@ -14,8 +17,31 @@ async fn tokio_main() -> Result<()> {
     initialize_panic_handler()?;
 
     let args = Cli::parse();
-    let hivemq_address = args.host + ":" + args.port.to_string().as_str();
-    let mut app = App::new(args.tick_rate, args.frame_rate, hivemq_address, args.debug)?;
+    let config = Config::new()?;
+    init_theme(config.theme);
+    let profile = args.profile(&config.config).map_err(|err| eyre!(err))?;
+    profile.connection().map_err(|err| eyre!(err))?;
+    if let Some(command) = args.command {
+        let output = run_command(
+            command,
+            &profile,
+            args.client_details_concurrency,
+            args.output,
+        )
+        .await
+        .map_err(|err| eyre!(err))?;
+        println!("{output}");
+        return Ok(());
+    }
+    let mut app = App::new(
+        config,
+        args.tick_rate,
+        args.frame_rate,
+        profile,
+        args.client_details_concurrency,
+        args.download_dir.unwrap_or_else(get_data_dir),
+        args.debug,
+    )?;
     app.run().await?;
 
     Ok(())
@@ -5,14 +5,25 @@ pub enum Mode {
     #[default]
     Home,
     EditorReadOnly,
+    DetailsEditor,
+    SearchInput,
     CreateEditor,
     UpdateEditor,
     Tab,
     ReadTab,
     ReadDeleteTab,
     BackupTab,
+    DiagnosticArchivesTab,
+    ClientsTab,
+    DataHubTab,
+    BehaviorPoliciesTab,
+    TraceRecordingsTab,
     FullTab,
     ErrorPopup,
     ConfirmPopup,
     FilterPopup,
+    InputPopup,
+    ProfilePopup,
+    PlanPopup,
+    ReferencesPane,
 }
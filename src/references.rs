@@ -0,0 +1,401 @@
+use std::sync::Arc;
+
+use hivemq_openapi::models::{BehaviorPolicy, DataPolicy};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::repository::Repository;
+use crate::services::behavior_policy_service::BehaviorPolicyService;
+use crate::services::data_hub_sync_service::ItemType;
+use crate::services::data_policy_service::DataPolicyService;
+
+/// The tables of the policies in the shared SQLite pool, as created by the policy tabs.
+const POLICY_TABLES: [(ItemType, &str); 2] = [
+    (ItemType::DataPolicy, "data_policies"),
+    (ItemType::BehaviorPolicy, "behavior_policies"),
+];
+
+/// A reference of a policy to a schema or a script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    pub policy_type: ItemType,
+    pub policy_id: String,
+    pub item_type: ItemType,
+    pub item_id: String,
+    /// The referenced version, e.g. `latest` or `2`
+    pub version: String,
+}
+
+impl Reference {
+    /// Returns the type and id of the other end of the reference, as seen from an item of the given type.
+    pub fn target(&self, from: ItemType) -> (ItemType, &str) {
+        if from == self.policy_type {
+            (self.item_type, &self.item_id)
+        } else {
+            (self.policy_type, &self.policy_id)
+        }
+    }
+}
+
+/// Loads the policies of the broker into the tables the references are read from,
+/// so no reference is missed if the policy tabs were not loaded yet.
+pub struct PolicyLoader {
+    data_policy_service: DataPolicyService,
+    behavior_policy_service: BehaviorPolicyService,
+}
+
+impl PolicyLoader {
+    pub fn new(
+        connection_pool: &Pool<SqliteConnectionManager>,
+        connection: &HivemqConnection,
+    ) -> Self {
+        let data_policy_repository = Repository::<DataPolicy>::init(
+            connection_pool,
+            "data_policies",
+            |val| val.id.clone(),
+            "lastUpdatedAt",
+        )
+        .unwrap();
+        let behavior_policy_repository = Repository::<BehaviorPolicy>::init(
+            connection_pool,
+            "behavior_policies",
+            |val| val.id.clone(),
+            "lastUpdatedAt",
+        )
+        .unwrap();
+        PolicyLoader {
+            data_policy_service: DataPolicyService::new(
+                Arc::new(data_policy_repository),
+                connection,
+            ),
+            behavior_policy_service: BehaviorPolicyService::new(
+                Arc::new(behavior_policy_repository),
+                connection,
+            ),
+        }
+    }
+
+    pub async fn load(&self) -> Result<(), String> {
+        self.data_policy_service.load_data_policies().await?;
+        self.behavior_policy_service.load_behavior_policies().await
+    }
+}
+
+/// Finds the references between the cached policies, schemas and scripts.
+///
+/// Schemas are referenced by a `schemaId` with a `version` or `schemaVersion`, e.g. in the
+/// schema validators of data policies and the arguments of `Serdes` functions.
+/// Scripts are referenced by the function ids `fn:<script id>:<version>` of policy pipelines.
+pub struct DataHubReferences {
+    connection_pool: Pool<SqliteConnectionManager>,
+}
+
+impl DataHubReferences {
+    pub fn new(connection_pool: &Pool<SqliteConnectionManager>) -> Self {
+        DataHubReferences {
+            connection_pool: connection_pool.clone(),
+        }
+    }
+
+    /// Returns the references of the cached policies,
+    /// ordered by policy and then by the type and id of the referenced item.
+    pub fn find_all(&self) -> Result<Vec<Reference>, String> {
+        let mut references = Vec::new();
+        for (policy_type, table_name) in POLICY_TABLES {
+            let mut policy_references = self.find_in_table(policy_type, table_name)?;
+            policy_references.sort_by(|a, b| {
+                (&a.policy_id, a.item_type, &a.item_id, &a.version).cmp(&(
+                    &b.policy_id,
+                    b.item_type,
+                    &b.item_id,
+                    &b.version,
+                ))
+            });
+            policy_references.dedup();
+            references.append(&mut policy_references);
+        }
+        Ok(references)
+    }
+
+    /// Returns the references of a policy or the references to a schema or script.
+    pub fn find_by_item(&self, item_type: ItemType, id: &str) -> Result<Vec<Reference>, String> {
+        let references = self
+            .find_all()?
+            .into_iter()
+            .filter(|reference| {
+                (reference.policy_type == item_type && reference.policy_id == id)
+                    || (reference.item_type == item_type && reference.item_id == id)
+            })
+            .collect();
+        Ok(references)
+    }
+
+    fn find_in_table(
+        &self,
+        policy_type: ItemType,
+        table_name: &str,
+    ) -> Result<Vec<Reference>, String> {
+        let connection = self.connection_pool.get().map_err(|err| err.to_string())?;
+        let mut stmt = connection
+            .prepare(&format!(
+                "
+        SELECT policy.id, node.key, node.value,
+            CAST(coalesce(
+                json_extract(policy.data, node.path || '.version'),
+                json_extract(policy.data, node.path || '.schemaVersion')
+            ) AS TEXT)
+        FROM {table_name} AS policy, json_tree(policy.data) AS node
+        WHERE node.key IN ('schemaId', 'functionId')
+        "
+            ))
+            .map_err(|err| err.to_string())?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let policy_id: String = row.get(0)?;
+                let key: String = row.get(1)?;
+                let value: String = row.get(2)?;
+                let version: Option<String> = row.get(3)?;
+                Ok((policy_id, key, value, version))
+            })
+            .map_err(|err| err.to_string())?;
+
+        let mut references = Vec::new();
+        for row in rows {
+            let (policy_id, key, value, version) = row.map_err(|err| err.to_string())?;
+            let reference = if key == "schemaId" {
+                Reference {
+                    policy_type,
+                    policy_id,
+                    item_type: ItemType::Schema,
+                    item_id: value,
+                    version: version.unwrap_or_else(|| "latest".to_string()),
+                }
+            } else {
+                // Only scripts have function ids with the prefix 'fn:', e.g. not 'System.log'
+                let Some(script) = value.strip_prefix("fn:") else {
+                    continue;
+                };
+                let (item_id, version) = script.split_once(':').unwrap_or((script, "latest"));
+                Reference {
+                    policy_type,
+                    policy_id,
+                    item_type: ItemType::Script,
+                    item_id: item_id.to_string(),
+                    version: version.to_string(),
+                }
+            };
+            references.push(reference);
+        }
+        Ok(references)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hivemq_openapi::models::{BehaviorPolicy, DataPolicy};
+    use httpmock::{Method::GET, MockServer};
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::hivemq_rest_client::HivemqConnection;
+    use crate::references::{DataHubReferences, PolicyLoader, Reference};
+    use crate::repository::Repository;
+    use crate::services::data_hub_sync_service::ItemType;
+    use crate::sqlite::init_sqlite;
+
+    fn reference(
+        policy_type: ItemType,
+        policy_id: &str,
+        item_type: ItemType,
+        item_id: &str,
+        version: &str,
+    ) -> Reference {
+        Reference {
+            policy_type,
+            policy_id: policy_id.to_string(),
+            item_type,
+            item_id: item_id.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    fn init_references() -> DataHubReferences {
+        let sqlite_pool = init_sqlite();
+        let data_policies = Repository::<DataPolicy>::init(
+            &sqlite_pool,
+            "data_policies",
+            |val| val.id.clone(),
+            "createdAt",
+        )
+        .unwrap();
+        let behavior_policies = Repository::<BehaviorPolicy>::init(
+            &sqlite_pool,
+            "behavior_policies",
+            |val| val.id.clone(),
+            "createdAt",
+        )
+        .unwrap();
+
+        let data_policy: DataPolicy = serde_json::from_value(json!({
+            "id": "data-policy",
+            "matching": { "topicFilter": "topic/#" },
+            "validation": {
+                "validators": [{
+                    "type": "schema",
+                    "arguments": {
+                        "strategy": "ALL_OF",
+                        "schemas": [
+                            { "schemaId": "schema-1", "version": "latest" },
+                            { "schemaId": "schema-2", "version": "2" }
+                        ]
+                    }
+                }]
+            },
+            "onSuccess": {
+                "pipeline": [
+                    {
+                        "id": "deserialize",
+                        "functionId": "Serdes.deserialize",
+                        "arguments": { "schemaId": "schema-1", "schemaVersion": "latest" }
+                    },
+                    {
+                        "id": "transform",
+                        "functionId": "fn:script-1:latest",
+                        "arguments": {}
+                    }
+                ]
+            }
+        }))
+        .unwrap();
+        data_policies.save(&data_policy).unwrap();
+
+        let behavior_policy: BehaviorPolicy = serde_json::from_value(json!({
+            "id": "behavior-policy",
+            "matching": { "clientIdRegex": ".*" },
+            "behavior": { "id": "Mqtt.events" },
+            "onTransitions": [{
+                "fromState": "Any.*",
+                "toState": "Any.*",
+                "Mqtt.OnInboundPublish": {
+                    "pipeline": [
+                        { "id": "log", "functionId": "System.log", "arguments": {} },
+                        { "id": "script", "functionId": "fn:script-1:3", "arguments": {} }
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+        behavior_policies.save(&behavior_policy).unwrap();
+
+        DataHubReferences::new(&sqlite_pool)
+    }
+
+    #[test]
+    fn test_find_by_policy() {
+        let references = init_references();
+
+        let result = references
+            .find_by_item(ItemType::DataPolicy, "data-policy")
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                reference(
+                    ItemType::DataPolicy,
+                    "data-policy",
+                    ItemType::Schema,
+                    "schema-1",
+                    "latest"
+                ),
+                reference(
+                    ItemType::DataPolicy,
+                    "data-policy",
+                    ItemType::Schema,
+                    "schema-2",
+                    "2"
+                ),
+                reference(
+                    ItemType::DataPolicy,
+                    "data-policy",
+                    ItemType::Script,
+                    "script-1",
+                    "latest"
+                ),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn test_find_by_script() {
+        let references = init_references();
+
+        let result = references
+            .find_by_item(ItemType::Script, "script-1")
+            .unwrap();
+
+        let targets: Vec<(ItemType, &str, &str)> = result
+            .iter()
+            .map(|reference| {
+                let (item_type, id) = reference.target(ItemType::Script);
+                (item_type, id, reference.version.as_str())
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (ItemType::DataPolicy, "data-policy", "latest"),
+                (ItemType::BehaviorPolicy, "behavior-policy", "3"),
+            ],
+            targets
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policy_loader() {
+        let broker = MockServer::start();
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v1/data-hub/data-validation/policies");
+            then.status(200).json_body(json!({
+                "items": [{
+                    "id": "data-policy",
+                    "matching": { "topicFilter": "topic/#" },
+                    "validation": {
+                        "validators": [{
+                            "type": "schema",
+                            "arguments": {
+                                "strategy": "ALL_OF",
+                                "schemas": [{ "schemaId": "schema-1", "version": "latest" }]
+                            }
+                        }]
+                    }
+                }]
+            }));
+        });
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v1/data-hub/behavior-validation/policies");
+            then.status(200).json_body(json!({ "items": [] }));
+        });
+        let sqlite_pool = init_sqlite();
+        let loader = PolicyLoader::new(&sqlite_pool, &HivemqConnection::new(broker.base_url()));
+
+        loader.load().await.unwrap();
+
+        let result = DataHubReferences::new(&sqlite_pool)
+            .find_by_item(ItemType::Schema, "schema-1")
+            .unwrap();
+        assert_eq!(
+            vec![reference(
+                ItemType::DataPolicy,
+                "data-policy",
+                ItemType::Schema,
+                "schema-1",
+                "latest"
+            )],
+            result
+        );
+    }
+}
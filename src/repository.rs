@@ -20,6 +20,13 @@ impl From<rusqlite::Error> for RepositoryError {
     }
 }
 
+pub fn transform_repository_err(err: RepositoryError) -> String {
+    match err {
+        RepositoryError::SerdeError(err) => err.to_string(),
+        RepositoryError::SqlError(err) => err.to_string(),
+    }
+}
+
 pub struct Repository<T: Serialize + DeserializeOwned> {
     connection_pool: r2d2::Pool<SqliteConnectionManager>,
     table_name: String,
@@ -235,7 +242,7 @@ impl<'a, T: Serialize + DeserializeOwned> Repository<T> {
             .map(|_size| ())?)
     }
 
-    fn delete_all(&self) -> Result<(), RepositoryError> {
+    pub fn delete_all(&self) -> Result<(), RepositoryError> {
         let table_name = &self.table_name;
         Ok(self
             .connection_pool
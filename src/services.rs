@@ -1,7 +1,12 @@
 pub mod backups_service;
 pub mod behavior_policy_service;
 pub mod client_details_service;
+pub mod client_state_service;
+pub mod data_hub_export_service;
+pub mod data_hub_service;
+pub mod data_hub_sync_service;
 pub mod data_policy_service;
+pub mod diagnostic_archive_service;
 pub mod schema_service;
 pub mod scripts_service;
 pub mod trace_recordings_service;
@@ -1,11 +1,17 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use hivemq_openapi::apis::backup_restore_api::get_all_backups;
+use hivemq_openapi::apis::backup_restore_api::{
+    get_all_backups, get_backup, restore_backup, GetBackupParams, RestoreBackupParams,
+};
 use hivemq_openapi::apis::configuration::Configuration;
-use hivemq_openapi::models::Backup;
+use hivemq_openapi::models::backup::State;
+use hivemq_openapi::models::{Backup, BackupItem};
+use tokio_util::sync::CancellationToken;
 
 use crate::hivemq_rest_client;
-use crate::hivemq_rest_client::transform_api_err;
+use crate::hivemq_rest_client::{transform_api_err, HivemqConnection};
 use crate::repository::Repository;
 
 pub struct BackupService {
@@ -14,8 +20,8 @@ pub struct BackupService {
 }
 
 impl BackupService {
-    pub fn new(repository: Arc<Repository<Backup>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
+    pub fn new(repository: Arc<Repository<Backup>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
         BackupService { repository, config }
     }
 
@@ -31,6 +37,55 @@ impl BackupService {
         Ok(())
     }
 
+    /// Reloads the backups and returns the backups that were running before and are not anymore,
+    /// and the ids of the running backups that are not on the broker anymore.
+    /// These are removed from the repository.
+    pub async fn refresh_running_backups(&self) -> Result<(Vec<Backup>, Vec<String>), String> {
+        let running_ids: Vec<String> = self
+            .find_running_backups()
+            .into_iter()
+            .filter_map(|backup| backup.id)
+            .collect();
+
+        let response = get_all_backups(&self.config)
+            .await
+            .map_err(transform_api_err)?;
+        let backups = response.items.unwrap_or_default();
+        for backup in &backups {
+            self.repository.save(backup).unwrap();
+        }
+
+        let mut finished = vec![];
+        let mut removed = vec![];
+        for backup_id in running_ids {
+            match backups
+                .iter()
+                .find(|backup| backup.id.as_ref() == Some(&backup_id))
+            {
+                Some(backup) if !is_running(backup) => finished.push(backup.clone()),
+                Some(_) => {}
+                None => {
+                    self.repository.delete_by_id(&backup_id).unwrap();
+                    removed.push(backup_id);
+                }
+            }
+        }
+        Ok((finished, removed))
+    }
+
+    pub fn has_running_backups(&self) -> bool {
+        !self.find_running_backups().is_empty()
+    }
+
+    fn find_running_backups(&self) -> Vec<Backup> {
+        self.repository
+            .find_all()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(is_running)
+            .collect()
+    }
+
     pub async fn start_backup(&self) -> Result<String, String> {
         let response = hivemq_openapi::apis::backup_restore_api::create_backup(&self.config)
             .await
@@ -43,20 +98,113 @@ impl BackupService {
             return Err(String::from("No backup was created"));
         }
     }
+
+    pub async fn restore_backup(&self, backup_id: &str) -> Result<String, String> {
+        let params = RestoreBackupParams {
+            backup_id: backup_id.to_string(),
+        };
+
+        let response = restore_backup(&self.config, params)
+            .await
+            .map_err(transform_api_err)?;
+
+        self.save_backup_item(response)
+    }
+
+    /// Polls the backup until it is neither being created nor restored anymore
+    /// and returns its final state.
+    /// Fails if the backup is still running after the timeout or if the token is cancelled.
+    pub async fn wait_for_backup(
+        &self,
+        backup_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+        cancellation_token: CancellationToken,
+    ) -> Result<Backup, String> {
+        let poll = async {
+            loop {
+                let params = GetBackupParams {
+                    backup_id: backup_id.to_string(),
+                };
+                let response = get_backup(&self.config, params)
+                    .await
+                    .map_err(transform_api_err)?;
+                let backup_id = self.save_backup_item(response)?;
+                let backup = self
+                    .repository
+                    .find_by_id(&backup_id)
+                    .map_err(|err| format!("{err:?}"))?;
+
+                if !is_running(&backup) {
+                    return Ok(backup);
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+
+        tokio::select! {
+            _ = cancellation_token.cancelled() => {
+                Err(format!("Waiting for backup '{backup_id}' was cancelled"))
+            }
+            result = tokio::time::timeout(timeout, poll) => result.unwrap_or_else(|_| {
+                Err(format!(
+                    "Backup '{backup_id}' did not finish within {} seconds",
+                    timeout.as_secs()
+                ))
+            }),
+        }
+    }
+
+    /// Downloads the backup file into the directory and returns the path of the file.
+    pub async fn download_backup<F: FnMut(u64, Option<u64>)>(
+        &self,
+        backup_id: &str,
+        directory: &Path,
+        on_progress: F,
+    ) -> Result<PathBuf, String> {
+        let path = format!(
+            "/api/v1/management/files/backups/{}",
+            hivemq_openapi::apis::urlencode(backup_id)
+        );
+        hivemq_rest_client::download_file(&self.config, &path, directory, backup_id, on_progress)
+            .await
+    }
+
+    fn save_backup_item(&self, backup_item: BackupItem) -> Result<String, String> {
+        let backup = backup_item
+            .backup
+            .ok_or_else(|| String::from("Response did not contain a backup"))?;
+        self.repository.save(&backup).unwrap();
+        backup.id.ok_or_else(|| String::from("Backup id was empty"))
+    }
+}
+
+fn is_running(backup: &Backup) -> bool {
+    matches!(
+        backup.state,
+        Some(State::InProgress | State::RestoreInProgress)
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
-    use hivemq_openapi::apis::backup_restore_api::{CreateBackupError, GetAllBackupsError};
-    use hivemq_openapi::models::{Backup, BackupList, Errors};
+    use std::time::Duration;
+
+    use hivemq_openapi::apis::backup_restore_api::{
+        CreateBackupError, GetAllBackupsError, RestoreBackupError,
+    };
+    use hivemq_openapi::models::backup::State;
+    use hivemq_openapi::models::{Backup, BackupItem, BackupList, Errors};
     use httpmock::Method::{GET, POST};
     use httpmock::MockServer;
     use r2d2::Pool;
     use r2d2_sqlite::SqliteConnectionManager;
     use serde_json::json;
+    use tokio_util::sync::CancellationToken;
 
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
     use crate::services::backups_service::BackupService;
 
@@ -93,7 +241,7 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = BackupService::new(repo.clone(), &broker.base_url());
+        let service = BackupService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
         (broker, connection_pool, repo, service)
     }
 
@@ -165,4 +313,227 @@ mod tests {
 
         assert!(response.is_err());
     }
+
+    #[tokio::test]
+    async fn test_restore_backup() {
+        let (broker, _pool, repo, service) = setup();
+
+        let mut backup = build_backup(1);
+        backup.state = Some(State::RestoreInProgress);
+        let backup_item = BackupItem {
+            backup: Some(Box::new(backup.clone())),
+        };
+        let restore_mock = broker.mock(|when, then| {
+            when.method(POST)
+                .path_contains("/api/v1/management/backups/");
+            then.status(200)
+                .body(serde_json::to_string(&backup_item).unwrap());
+        });
+
+        let backup_id = service
+            .restore_backup(&backup.id.clone().unwrap())
+            .await
+            .unwrap();
+
+        restore_mock.assert();
+        assert_eq!(backup, repo.find_by_id(&backup_id).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_restore_backup_error() {
+        let (broker, _pool, _repo, service) = setup();
+
+        let error = RestoreBackupError::Status404(Errors::new());
+        broker.mock(|when, then| {
+            when.any_request().method(POST);
+            then.status(404)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let response = service.restore_backup("backup-1").await;
+
+        assert!(response.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_backup() {
+        let (broker, _pool, repo, service) = setup();
+
+        let mut backup = build_backup(1);
+        backup.state = Some(State::RestoreFailed);
+        backup.fail_reason = Some(Some("Restore failed".to_string()));
+        let backup_item = BackupItem {
+            backup: Some(Box::new(backup.clone())),
+        };
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path_contains("/api/v1/management/backups/");
+            then.status(200)
+                .body(serde_json::to_string(&backup_item).unwrap());
+        });
+
+        let result = service
+            .wait_for_backup(
+                &backup.id.clone().unwrap(),
+                Duration::from_millis(10),
+                Duration::from_secs(10),
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            backup,
+            repo.find_by_id(&backup.id.clone().unwrap()).unwrap()
+        );
+        assert_eq!(backup, result);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_backup_timeout() {
+        let (broker, _pool, _repo, service) = setup();
+
+        let mut backup = build_backup(1);
+        backup.state = Some(State::RestoreInProgress);
+        let backup_item = BackupItem {
+            backup: Some(Box::new(backup.clone())),
+        };
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path_contains("/api/v1/management/backups/");
+            then.status(200)
+                .body(serde_json::to_string(&backup_item).unwrap());
+        });
+
+        let result = service
+            .wait_for_backup(
+                &backup.id.clone().unwrap(),
+                Duration::from_millis(10),
+                Duration::from_millis(50),
+                CancellationToken::new(),
+            )
+            .await;
+
+        assert!(result.unwrap_err().contains("did not finish"));
+    }
+
+    #[tokio::test]
+    async fn test_download_backup() {
+        let (broker, _pool, _repo, service) = setup();
+
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path_contains("/api/v1/management/files/backups/");
+            then.status(200)
+                .header(
+                    "content-disposition",
+                    "attachment; filename=\"backup-1.hivemq\"",
+                )
+                .body("backup content");
+        });
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().to_path_buf();
+
+        let mut progress = vec![];
+        let path = service
+            .download_backup("backup-1", &directory, |downloaded, total| {
+                progress.push((downloaded, total))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(directory.join("backup-1.hivemq"), path);
+        assert_eq!("backup content", std::fs::read_to_string(&path).unwrap());
+        assert_eq!(Some(&(14, Some(14))), progress.last());
+
+        let second_path = service
+            .download_backup("backup-1", &directory, |_, _| {})
+            .await
+            .unwrap();
+
+        assert_eq!(directory.join("backup-1 (1).hivemq"), second_path);
+        assert_eq!("backup content", std::fs::read_to_string(&path).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_download_backup_error() {
+        let (broker, _pool, _repo, service) = setup();
+
+        broker.mock(|when, then| {
+            when.method(GET);
+            then.status(404);
+        });
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().to_path_buf();
+
+        let result = service
+            .download_backup("backup-1", &directory, |_, _| {})
+            .await;
+
+        assert!(result.is_err());
+        assert!(!directory.join("backup-1").exists());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_running_backups() {
+        let (broker, _pool, repo, service) = setup();
+
+        let mut running = build_backup(1);
+        running.state = Some(State::InProgress);
+        let mut completed = build_backup(2);
+        completed.state = Some(State::Completed);
+        let mut restoring = build_backup(3);
+        restoring.state = Some(State::RestoreInProgress);
+        repo.save(&running).unwrap();
+        repo.save(&completed).unwrap();
+        repo.save(&restoring).unwrap();
+        assert!(service.has_running_backups());
+
+        let mut finished = running.clone();
+        finished.state = Some(State::Completed);
+        let mut restored = restoring.clone();
+        restored.state = Some(State::RestoreCompleted);
+        let backup_list = BackupList {
+            items: Some(vec![finished.clone(), completed, restored.clone()]),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/management/backups");
+            then.status(200)
+                .body(serde_json::to_string(&backup_list).unwrap());
+        });
+
+        let (result, removed) = service.refresh_running_backups().await.unwrap();
+
+        assert_eq!(2, result.len());
+        assert!(result.contains(&finished));
+        assert!(result.contains(&restored));
+        assert!(removed.is_empty());
+        assert_eq!(finished, repo.find_by_id("backup-$1").unwrap());
+        assert!(!service.has_running_backups());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_removed_running_backups() {
+        let (broker, _pool, repo, service) = setup();
+
+        let mut running = build_backup(1);
+        running.state = Some(State::InProgress);
+        repo.save(&running).unwrap();
+
+        let backup_list = BackupList {
+            items: Some(vec![]),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/management/backups");
+            then.status(200)
+                .body(serde_json::to_string(&backup_list).unwrap());
+        });
+
+        let (finished, removed) = service.refresh_running_backups().await.unwrap();
+
+        assert!(finished.is_empty());
+        assert_eq!(vec!["backup-$1".to_string()], removed);
+        assert!(repo.find_by_id("backup-$1").is_err());
+        assert!(!service.has_running_backups());
+    }
 }
@@ -8,7 +8,7 @@ use hivemq_openapi::apis::data_hub_behavior_policies_api::{
 use hivemq_openapi::models::BehaviorPolicy;
 
 use crate::hivemq_rest_client;
-use crate::hivemq_rest_client::{get_cursor, transform_api_err};
+use crate::hivemq_rest_client::{get_cursor, transform_api_err, HivemqConnection};
 use crate::repository::Repository;
 
 pub struct BehaviorPolicyService {
@@ -17,8 +17,8 @@ pub struct BehaviorPolicyService {
 }
 
 impl BehaviorPolicyService {
-    pub fn new(repository: Arc<Repository<BehaviorPolicy>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
+    pub fn new(repository: Arc<Repository<BehaviorPolicy>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
         BehaviorPolicyService { repository, config }
     }
 
@@ -128,6 +128,7 @@ mod tests {
     use r2d2_sqlite::SqliteConnectionManager;
     use serde_json::json;
 
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
     use crate::services::behavior_policy_service::BehaviorPolicyService;
 
@@ -168,7 +169,8 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = BehaviorPolicyService::new(repo.clone(), &broker.base_url());
+        let service =
+            BehaviorPolicyService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
         (broker, connection_pool, repo, service)
     }
 
@@ -1,39 +1,348 @@
 use crate::hivemq_rest_client;
-use crate::repository::{Repository, RepositoryError};
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::repository::{transform_repository_err, Repository};
+use futures::{stream, StreamExt};
 use hivemq_openapi::apis::configuration::Configuration;
 use hivemq_openapi::apis::mqtt_clients_api::{
-    get_all_mqtt_clients, get_mqtt_client_details, GetAllMqttClientsParams,
-    GetMqttClientDetailsParams,
+    disconnect_client, get_all_mqtt_clients, get_mqtt_client_details,
+    get_subscriptions_for_mqtt_client, invalidate_client_session, DisconnectClientParams,
+    GetAllMqttClientsParams, GetMqttClientDetailsParams, GetSubscriptionsForMqttClientParams,
+    InvalidateClientSessionParams,
 };
-use hivemq_openapi::models::ClientDetails;
+use hivemq_openapi::apis::Error;
+use hivemq_openapi::models::{ClientDetails, ClientSubscription};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 
+use std::path::Path;
 use std::sync::Arc;
 
+/// The maximum number of loaded client details that are reported in a single progress update.
+const PROGRESS_BATCH_SIZE: usize = 250;
+
+const CANCELLED: &str = "Loading client details was cancelled";
+
+/// The subscriptions of a single client as they are cached in the subscriptions repository.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientSubscriptions {
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub subscriptions: Vec<ClientSubscription>,
+}
+
+/// The result of a bulk operation for every affected client id.
+pub type BulkResults = Vec<(String, Result<(), String>)>;
+
 pub struct ClientDetailsService {
     repository: Arc<Repository<ClientDetails>>,
+    subscription_repository: Arc<Repository<ClientSubscriptions>>,
     config: Configuration,
 }
 
 impl ClientDetailsService {
-    pub fn new(repository: Arc<Repository<ClientDetails>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
-        ClientDetailsService { repository, config }
+    pub fn new(
+        repository: Arc<Repository<ClientDetails>>,
+        subscription_repository: Arc<Repository<ClientSubscriptions>>,
+        connection: &HivemqConnection,
+    ) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
+        ClientDetailsService {
+            repository,
+            subscription_repository,
+            config,
+        }
     }
 
-    pub async fn load_details(&self) -> Result<(), String> {
-        let client_ids = self.fetch_client_ids().await?;
-        for client_id in client_ids {
-            let (_, client_details) = self.fetch_client_details(&client_id).await?;
-            self.repository
-                .save(&client_details)
-                .map_err(|err| match err {
-                    RepositoryError::SerdeError(err) => err.to_string(),
-                    RepositoryError::SqlError(err) => err.to_string(),
-                })?;
+    /// Replaces the cached client details with the details of all currently known clients.
+    ///
+    /// The details are fetched with at most `concurrency` concurrent requests. Whenever a batch of
+    /// details was saved, `on_progress` is called with the ids of that batch, the number of
+    /// fetched clients and the total number of clients.
+    ///
+    /// Clients whose details can not be fetched, e.g. because they were removed in the meantime,
+    /// are skipped. Their errors are returned.
+    pub async fn load_details<F>(
+        &self,
+        concurrency: usize,
+        cancellation_token: CancellationToken,
+        mut on_progress: F,
+    ) -> Result<Vec<String>, String>
+    where
+        F: FnMut(Vec<String>, usize, usize),
+    {
+        self.repository
+            .delete_all()
+            .map_err(transform_repository_err)?;
+
+        let client_ids = tokio::select! {
+            biased;
+            _ = cancellation_token.cancelled() => return Err(CANCELLED.to_string()),
+            client_ids = self.fetch_client_ids() => client_ids?,
+        };
+        let total = client_ids.len();
+        on_progress(vec![], 0, total);
+
+        let mut batches = stream::iter(client_ids)
+            .map(|client_id| async move {
+                self.fetch_client_details(&client_id)
+                    .await
+                    .map_err(|message| format!("Client '{client_id}': {message}"))
+            })
+            .buffer_unordered(concurrency.max(1))
+            .ready_chunks(PROGRESS_BATCH_SIZE);
+
+        let mut fetched = 0;
+        let mut errors = vec![];
+        loop {
+            // Checks the cancellation first, so no batch is saved after it
+            let batch = tokio::select! {
+                biased;
+                _ = cancellation_token.cancelled() => return Err(CANCELLED.to_string()),
+                batch = batches.next() => batch,
+            };
+            let Some(batch) = batch else {
+                break;
+            };
+
+            fetched += batch.len();
+            let mut batch_ids = Vec::with_capacity(batch.len());
+            for result in batch {
+                match result {
+                    Ok((client_id, client_details)) => {
+                        self.repository
+                            .save(&client_details)
+                            .map_err(transform_repository_err)?;
+                        batch_ids.push(client_id);
+                    }
+                    Err(message) => errors.push(message),
+                }
+            }
+            on_progress(batch_ids, fetched, total);
         }
+
+        Ok(errors)
+    }
+
+    /// Fetches and caches the details of a single client.
+    pub async fn load_client_details(&self, client_id: &str) -> Result<ClientDetails, String> {
+        let (_, client_details) = self.fetch_client_details(client_id).await?;
+        self.repository
+            .save(&client_details)
+            .map_err(transform_repository_err)?;
+        Ok(client_details)
+    }
+
+    /// Returns the ids of the cached clients or, if no client is cached, the ids of all clients of the broker.
+    pub async fn find_client_ids(&self) -> Result<Vec<String>, String> {
+        let client_ids = self
+            .repository
+            .find_all_ids()
+            .map_err(transform_repository_err)?;
+        if !client_ids.is_empty() {
+            return Ok(client_ids);
+        }
+        self.fetch_client_ids().await
+    }
+
+    /// Fetches and caches the details of the given clients that are not cached yet,
+    /// running at most `concurrency` requests at a time.
+    /// Returns the ids of the clients whose details are cached, without the clients that are gone.
+    pub async fn load_missing_details(
+        &self,
+        client_ids: Vec<String>,
+        concurrency: usize,
+    ) -> Vec<String> {
+        stream::iter(client_ids)
+            .map(|client_id| async move {
+                if self.repository.find_by_id(&client_id).is_ok() {
+                    return Some(client_id);
+                }
+                match self.load_client_details(&client_id).await {
+                    Ok(_) => Some(client_id),
+                    Err(message) => {
+                        log::debug!("Skipping client without details: {message}");
+                        None
+                    }
+                }
+            })
+            .buffered(concurrency.max(1))
+            .filter_map(|client_id| async move { client_id })
+            .collect()
+            .await
+    }
+
+    pub async fn load_subscriptions(&self, client_id: &str) -> Result<(), String> {
+        let params = GetSubscriptionsForMqttClientParams {
+            client_id: client_id.to_string(),
+        };
+
+        let response = get_subscriptions_for_mqtt_client(&self.config, params)
+            .await
+            .map_err(hivemq_rest_client::transform_api_err)?;
+
+        let subscriptions = ClientSubscriptions {
+            client_id: client_id.to_string(),
+            subscriptions: response.items.unwrap_or_default(),
+        };
+        self.subscription_repository
+            .save(&subscriptions)
+            .map_err(transform_repository_err)?;
+
         Ok(())
     }
 
+    /// Disconnects the client and refreshes its cached details.
+    /// Clients whose session ends with the disconnect are not found anymore,
+    /// only those are removed from the cache.
+    pub async fn disconnect_client(
+        &self,
+        client_id: &str,
+        prevent_will_message: bool,
+    ) -> Result<String, String> {
+        self.request_disconnect(client_id, prevent_will_message)
+            .await?;
+
+        let params = GetMqttClientDetailsParams {
+            client_id: client_id.to_string(),
+        };
+        match get_mqtt_client_details(&self.config, params).await {
+            Ok(response) => {
+                if let Some(client_details) = response.client {
+                    self.repository
+                        .save(&client_details)
+                        .map_err(transform_repository_err)?;
+                }
+            }
+            Err(Error::ResponseError(response)) if response.status == StatusCode::NOT_FOUND => {
+                self.repository
+                    .delete_by_id(client_id)
+                    .map_err(transform_repository_err)?;
+            }
+            Err(err) => {
+                return Err(format!(
+                    "Client '{client_id}' was disconnected, but refreshing it failed: {}",
+                    hivemq_rest_client::transform_api_err(err)
+                ));
+            }
+        }
+
+        Ok(client_id.to_string())
+    }
+
+    /// Disconnects the client and removes its cached subscriptions.
+    async fn request_disconnect(
+        &self,
+        client_id: &str,
+        prevent_will_message: bool,
+    ) -> Result<(), String> {
+        let params = DisconnectClientParams {
+            client_id: client_id.to_string(),
+            prevent_will_message: Some(prevent_will_message),
+        };
+
+        disconnect_client(&self.config, params)
+            .await
+            .map_err(hivemq_rest_client::transform_api_err)?;
+
+        self.subscription_repository
+            .delete_by_id(client_id)
+            .map_err(transform_repository_err)
+    }
+
+    pub async fn invalidate_client_session(
+        &self,
+        client_id: &str,
+        prevent_will_message: bool,
+    ) -> Result<String, String> {
+        let params = InvalidateClientSessionParams {
+            client_id: client_id.to_string(),
+            prevent_will_message: Some(prevent_will_message),
+        };
+
+        invalidate_client_session(&self.config, params)
+            .await
+            .map_err(hivemq_rest_client::transform_api_err)?;
+
+        self.subscription_repository
+            .delete_by_id(client_id)
+            .map_err(transform_repository_err)?;
+        self.repository
+            .delete_by_id(client_id)
+            .map_err(transform_repository_err)?;
+
+        Ok(client_id.to_string())
+    }
+
+    /// Disconnects all given clients, running at most `concurrency` requests at a time.
+    /// Returns the result for every client in the order of the given ids.
+    /// The cached details are not refreshed, the clients have to be reloaded afterwards.
+    pub async fn disconnect_clients(
+        &self,
+        client_ids: Vec<String>,
+        prevent_will_message: bool,
+        concurrency: usize,
+    ) -> BulkResults {
+        stream::iter(client_ids)
+            .map(|client_id| async move {
+                let result = self
+                    .request_disconnect(&client_id, prevent_will_message)
+                    .await;
+                (client_id, result)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Invalidates the sessions of all given clients, running at most `concurrency` requests at a time.
+    /// Returns the result for every client in the order of the given ids.
+    pub async fn invalidate_client_sessions(
+        &self,
+        client_ids: Vec<String>,
+        prevent_will_message: bool,
+        concurrency: usize,
+    ) -> BulkResults {
+        stream::iter(client_ids)
+            .map(|client_id| async move {
+                let result = self
+                    .invalidate_client_session(&client_id, prevent_will_message)
+                    .await
+                    .map(|_| ());
+                (client_id, result)
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Writes the cached details of all given clients as a JSON array to the given file.
+    pub fn export_clients(
+        &self,
+        client_ids: &[String],
+        path: &Path,
+    ) -> Result<BulkResults, String> {
+        let mut results = Vec::with_capacity(client_ids.len());
+        let mut exported = Vec::with_capacity(client_ids.len());
+        for client_id in client_ids {
+            match self.repository.find_by_id(client_id) {
+                Ok(client_details) => {
+                    exported.push(client_details);
+                    results.push((client_id.clone(), Ok(())));
+                }
+                Err(err) => results.push((client_id.clone(), Err(transform_repository_err(err)))),
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&exported).map_err(|err| err.to_string())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        std::fs::write(path, json).map_err(|err| err.to_string())?;
+
+        Ok(results)
+    }
+
     async fn fetch_client_details(
         &self,
         client_id: &str,
@@ -85,15 +394,24 @@ impl ClientDetailsService {
 
 #[cfg(test)]
 mod tests {
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
-    use crate::services::client_details_service::ClientDetailsService;
+    use crate::services::client_details_service::{ClientDetailsService, ClientSubscriptions};
+    use hivemq_openapi::apis::mqtt_clients_api::{
+        DisconnectClientError, GetSubscriptionsForMqttClientError, InvalidateClientSessionError,
+    };
+    use hivemq_openapi::models::client_subscription::{Qos, RetainHandling};
     use hivemq_openapi::models::{
-        Client, ClientDetails, ClientItem, ClientList, ClientRestrictions, ConnectionDetails,
+        Client, ClientDetails, ClientItem, ClientList, ClientRestrictions, ClientSubscription,
+        ClientSubscriptionList, ConnectionDetails, Errors,
     };
-    use httpmock::Method::GET;
+    use httpmock::Method::{DELETE, GET};
     use httpmock::MockServer;
+    use itertools::Itertools;
+    use r2d2::Pool;
     use r2d2_sqlite::SqliteConnectionManager;
     use std::sync::Arc;
+    use tokio_util::sync::CancellationToken;
 
     fn build_client_details(client_id: &str) -> ClientDetails {
         ClientDetails {
@@ -133,10 +451,25 @@ mod tests {
         client_items
     }
 
-    #[tokio::test]
-    async fn test_fetch_client_details_client() {
+    fn build_subscription(topic_filter: &str) -> ClientSubscription {
+        ClientSubscription {
+            no_local: Some(false),
+            qos: Some(Qos::AtLeastOnce),
+            retain_as_published: Some(false),
+            retain_handling: Some(RetainHandling::Send),
+            subscription_identifier: Some(Some(1)),
+            topic_filter: Some(topic_filter.to_string()),
+        }
+    }
+
+    fn setup() -> (
+        MockServer,
+        Arc<Repository<ClientDetails>>,
+        Arc<Repository<ClientSubscriptions>>,
+        ClientDetailsService,
+    ) {
         let broker = MockServer::start();
-        let connection_pool = r2d2::Pool::new(SqliteConnectionManager::memory()).unwrap();
+        let connection_pool = Pool::new(SqliteConnectionManager::memory()).unwrap();
         let repo = Repository::<ClientDetails>::init(
             &connection_pool,
             "test_values",
@@ -145,7 +478,25 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = ClientDetailsService::new(repo.clone(), &broker.base_url());
+        let subscription_repo = Repository::<ClientSubscriptions>::init(
+            &connection_pool,
+            "client_subscriptions",
+            |subscriptions| subscriptions.client_id.clone(),
+            "clientId",
+        )
+        .unwrap();
+        let subscription_repo = Arc::new(subscription_repo);
+        let service = ClientDetailsService::new(
+            repo.clone(),
+            subscription_repo.clone(),
+            &HivemqConnection::new(broker.base_url()),
+        );
+        (broker, repo, subscription_repo, service)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_client_details_client() {
+        let (broker, repo, _subscription_repo, service) = setup();
 
         let client_items = build_client_items(0, 5);
         let client_list = ClientList {
@@ -177,16 +528,431 @@ mod tests {
             });
         }
 
-        service.load_details().await.unwrap();
+        let mut progress = vec![];
+        service
+            .load_details(2, CancellationToken::new(), |client_ids, fetched, total| {
+                progress.push((client_ids.len(), fetched, total))
+            })
+            .await
+            .unwrap();
 
+        assert_eq!(Some(&(0, 0, 5)), progress.first());
+        assert_eq!(Some(&5), progress.last().map(|(_, fetched, _)| fetched));
+        assert_eq!(5, progress.iter().map(|(batch, _, _)| batch).sum::<usize>());
         let client_details = repo.find_all().unwrap();
         let client_ids: Vec<String> = client_details
             .iter()
             .map(|detail| detail.id.clone().unwrap())
+            .sorted()
             .collect();
         assert_eq!(
             vec!["client-0", "client-1", "client-2", "client-3", "client-4"],
             client_ids
         );
     }
+
+    #[tokio::test]
+    async fn test_load_details_replaces_cached_clients() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        repo.save(&build_client_details("stale-client")).unwrap();
+        let client_list = ClientList {
+            _links: None,
+            items: Some(build_client_items(0, 1)),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_list).unwrap());
+        });
+        let client_item = ClientItem {
+            client: Some(Box::new(build_client_details("client-0"))),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_item).unwrap());
+        });
+
+        service
+            .load_details(10, CancellationToken::new(), |_, _, _| {})
+            .await
+            .unwrap();
+
+        let client_ids = repo.find_all_ids().unwrap();
+        assert_eq!(vec!["client-0"], client_ids);
+    }
+
+    #[tokio::test]
+    async fn test_load_details_skips_failed_clients() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        let client_list = ClientList {
+            _links: None,
+            items: Some(build_client_items(0, 2)),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_list).unwrap());
+        });
+        let client_item = ClientItem {
+            client: Some(Box::new(build_client_details("client-0"))),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_item).unwrap());
+        });
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-1");
+            then.status(404);
+        });
+
+        let mut progress = vec![];
+        let errors = service
+            .load_details(10, CancellationToken::new(), |_, fetched, total| {
+                progress.push((fetched, total))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(1, errors.len());
+        assert!(errors[0].contains("client-1"));
+        assert_eq!(Some(&(2, 2)), progress.last());
+        assert_eq!(vec!["client-0"], repo.find_all_ids().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_find_client_ids_without_cache() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        let client_list = ClientList {
+            _links: None,
+            items: Some(build_client_items(0, 2)),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_list).unwrap());
+        });
+        let client_details = build_client_details("client-0");
+        let client_item = ClientItem {
+            client: Some(Box::new(client_details.clone())),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-0");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_item).unwrap());
+        });
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-1");
+            then.status(404);
+        });
+
+        let client_ids = service.find_client_ids().await.unwrap();
+        let loaded = service.load_missing_details(client_ids.clone(), 2).await;
+
+        assert_eq!(vec!["client-0", "client-1"], client_ids);
+        assert_eq!(vec!["client-0"], loaded);
+        assert_eq!(client_details, repo.find_by_id("client-0").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_load_details_cancelled() {
+        let (broker, _repo, _subscription_repo, service) = setup();
+
+        let client_list = ClientList {
+            _links: None,
+            items: Some(build_client_items(0, 1)),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_list).unwrap());
+        });
+
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+        let result = service
+            .load_details(10, cancellation_token, |_, _, _| {})
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_subscriptions() {
+        let (broker, _repo, subscription_repo, service) = setup();
+
+        let subscriptions = vec![build_subscription("topic/a"), build_subscription("topic/#")];
+        let subscription_list = ClientSubscriptionList {
+            _links: None,
+            items: Some(subscriptions.clone()),
+        };
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v1/mqtt/clients/client-1/subscriptions");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&subscription_list).unwrap());
+        });
+
+        service.load_subscriptions("client-1").await.unwrap();
+
+        assert_eq!(
+            ClientSubscriptions {
+                client_id: "client-1".to_string(),
+                subscriptions,
+            },
+            subscription_repo.find_by_id("client-1").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_subscriptions_error() {
+        let (broker, _repo, subscription_repo, service) = setup();
+
+        let error = GetSubscriptionsForMqttClientError::Status404(Errors::new());
+        broker.mock(|when, then| {
+            when.any_request();
+            then.status(404)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let result = service.load_subscriptions("client-1").await;
+
+        assert!(result.is_err());
+        assert!(subscription_repo.find_by_id("client-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_client() {
+        let (broker, repo, subscription_repo, service) = setup();
+
+        let mut client_details = build_client_details("client-1");
+        repo.save(&client_details).unwrap();
+        subscription_repo
+            .save(&ClientSubscriptions {
+                client_id: "client-1".to_string(),
+                subscriptions: vec![build_subscription("topic")],
+            })
+            .unwrap();
+
+        client_details.connected = Some(false);
+        let client_item = ClientItem {
+            client: Some(Box::new(client_details.clone())),
+        };
+        let disconnect_mock = broker.mock(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/mqtt/clients/client-1/connection")
+                .query_param("preventWillMessage", "true");
+            then.status(204);
+        });
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&client_item).unwrap());
+        });
+
+        let client_id = service.disconnect_client("client-1", true).await.unwrap();
+
+        disconnect_mock.assert();
+        assert_eq!("client-1", client_id);
+        assert_eq!(client_details, repo.find_by_id("client-1").unwrap());
+        assert!(subscription_repo.find_by_id("client-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_client_without_session() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        repo.save(&build_client_details("client-1")).unwrap();
+        broker.mock(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/mqtt/clients/client-1/connection");
+            then.status(204);
+        });
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-1");
+            then.status(404);
+        });
+
+        service.disconnect_client("client-1", false).await.unwrap();
+
+        assert!(repo.find_by_id("client-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_client_refresh_error() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        let client_details = build_client_details("client-1");
+        repo.save(&client_details).unwrap();
+        broker.mock(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/mqtt/clients/client-1/connection");
+            then.status(204);
+        });
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/mqtt/clients/client-1");
+            then.status(503);
+        });
+
+        let result = service.disconnect_client("client-1", false).await;
+
+        assert!(result.unwrap_err().contains("refreshing it failed"));
+        assert_eq!(client_details, repo.find_by_id("client-1").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_client_error() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        let client_details = build_client_details("client-1");
+        repo.save(&client_details).unwrap();
+        let error = DisconnectClientError::Status404(Errors::new());
+        broker.mock(|when, then| {
+            when.method(DELETE);
+            then.status(404)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let result = service.disconnect_client("client-1", false).await;
+
+        assert!(result.is_err());
+        assert_eq!(client_details, repo.find_by_id("client-1").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_client_session() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        repo.save(&build_client_details("client-1")).unwrap();
+        let invalidate_mock = broker.mock(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/mqtt/clients/client-1")
+                .query_param("preventWillMessage", "false");
+            then.status(204);
+        });
+
+        let client_id = service
+            .invalidate_client_session("client-1", false)
+            .await
+            .unwrap();
+
+        invalidate_mock.assert();
+        assert_eq!("client-1", client_id);
+        assert!(repo.find_by_id("client-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_client_session_error() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        repo.save(&build_client_details("client-1")).unwrap();
+        let error = InvalidateClientSessionError::Status404(Errors::new());
+        broker.mock(|when, then| {
+            when.method(DELETE);
+            then.status(404)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let result = service.invalidate_client_session("client-1", false).await;
+
+        assert!(result.is_err());
+        assert!(repo.find_by_id("client-1").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_clients() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        for client_id in ["client-1", "client-2", "client-3"] {
+            repo.save(&build_client_details(client_id)).unwrap();
+        }
+        for client_id in ["client-1", "client-3"] {
+            broker.mock(|when, then| {
+                when.method(DELETE)
+                    .path(format!("/api/v1/mqtt/clients/{client_id}/connection"));
+                then.status(204);
+            });
+        }
+        broker.mock(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/mqtt/clients/client-2/connection");
+            then.status(404).body(
+                serde_json::to_string(&DisconnectClientError::Status404(Errors::new())).unwrap(),
+            );
+        });
+        let details_mock = broker.mock(|when, then| {
+            when.method(GET);
+            then.status(404);
+        });
+
+        let client_ids = vec![
+            "client-1".to_string(),
+            "client-2".to_string(),
+            "client-3".to_string(),
+        ];
+        let results = service.disconnect_clients(client_ids, false, 2).await;
+
+        let ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(vec!["client-1", "client-2", "client-3"], ids);
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.is_ok());
+        details_mock.assert_hits(0);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_client_sessions() {
+        let (broker, repo, _subscription_repo, service) = setup();
+
+        for client_id in ["client-1", "client-2"] {
+            repo.save(&build_client_details(client_id)).unwrap();
+        }
+        let invalidate_mock = broker.mock(|when, then| {
+            when.method(DELETE)
+                .path_contains("/api/v1/mqtt/clients/client-")
+                .query_param("preventWillMessage", "true");
+            then.status(204);
+        });
+
+        let client_ids = vec!["client-1".to_string(), "client-2".to_string()];
+        let results = service
+            .invalidate_client_sessions(client_ids, true, 10)
+            .await;
+
+        invalidate_mock.assert_hits(2);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert!(repo.find_all().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_clients() {
+        let (_broker, repo, _subscription_repo, service) = setup();
+
+        let client_details = build_client_details("client-1");
+        repo.save(&client_details).unwrap();
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("exports").join("clients.json");
+
+        let client_ids = vec!["client-1".to_string(), "client-2".to_string()];
+        let results = service.export_clients(&client_ids, &path).unwrap();
+
+        assert!(results[0].1.is_ok());
+        assert!(results[1].1.is_err());
+        let exported: Vec<ClientDetails> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(vec![client_details], exported);
+    }
 }
@@ -0,0 +1,229 @@
+use std::sync::Arc;
+
+use futures::{stream, StreamExt};
+use hivemq_openapi::apis::configuration::Configuration;
+use hivemq_openapi::apis::data_hub_state_api::{get_client_state, GetClientStateParams};
+use hivemq_openapi::models::FsmStateInformationItem;
+use serde::{Deserialize, Serialize};
+
+use crate::hivemq_rest_client;
+use crate::hivemq_rest_client::{transform_api_err, HivemqConnection};
+use crate::repository::{transform_repository_err, Repository};
+
+/// The Data Hub FSM states of a single client, one for every behavior policy that applies to it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClientState {
+    #[serde(rename = "clientId")]
+    pub client_id: String,
+    pub states: Vec<FsmStateInformationItem>,
+}
+
+impl ClientState {
+    /// Returns true if the client is in the given state of the behavior policy.
+    /// Any state of the policy matches if no state name is given.
+    pub fn is_in_state(&self, policy_id: &str, state_name: Option<&str>) -> bool {
+        self.states.iter().any(|state| {
+            state.policy_id.as_deref() == Some(policy_id)
+                && state_name.is_none_or(|name| state.state_name.as_deref() == Some(name))
+        })
+    }
+}
+
+pub struct ClientStateService {
+    repository: Arc<Repository<ClientState>>,
+    config: Configuration,
+}
+
+impl ClientStateService {
+    pub fn new(repository: Arc<Repository<ClientState>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
+        ClientStateService { repository, config }
+    }
+
+    pub async fn load_client_state(&self, client_id: &str) -> Result<(), String> {
+        let client_state = self.fetch_client_state(client_id).await?;
+        self.repository
+            .save(&client_state)
+            .map_err(transform_repository_err)?;
+        Ok(())
+    }
+
+    /// Fetches the states of the given clients with at most `concurrency` concurrent requests
+    /// and returns the ids of the clients that are in the given state of the behavior policy.
+    /// Clients whose state can not be fetched, e.g. because they disconnected, are skipped.
+    pub async fn find_clients_in_state(
+        &self,
+        client_ids: Vec<String>,
+        policy_id: &str,
+        state_name: Option<&str>,
+        concurrency: usize,
+    ) -> Result<Vec<String>, String> {
+        let client_states: Vec<Result<ClientState, String>> = stream::iter(client_ids)
+            .map(|client_id| async move { self.fetch_client_state(&client_id).await })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut matching_client_ids = vec![];
+        for client_state in client_states {
+            let client_state = match client_state {
+                Ok(client_state) => client_state,
+                Err(message) => {
+                    log::debug!("Skipping client while searching client states: {message}");
+                    continue;
+                }
+            };
+            self.repository
+                .save(&client_state)
+                .map_err(transform_repository_err)?;
+            if client_state.is_in_state(policy_id, state_name) {
+                matching_client_ids.push(client_state.client_id);
+            }
+        }
+
+        Ok(matching_client_ids)
+    }
+
+    async fn fetch_client_state(&self, client_id: &str) -> Result<ClientState, String> {
+        let params = GetClientStateParams {
+            client_id: client_id.to_string(),
+        };
+
+        let response = get_client_state(&self.config, params)
+            .await
+            .map_err(transform_api_err)?;
+
+        Ok(ClientState {
+            client_id: client_id.to_string(),
+            states: response.items.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use hivemq_openapi::apis::data_hub_state_api::GetClientStateError;
+    use hivemq_openapi::models::{Errors, FsmStateInformationItem, FsmStatesInformationListItem};
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use r2d2::Pool;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    use crate::hivemq_rest_client::HivemqConnection;
+    use crate::repository::Repository;
+    use crate::services::client_state_service::{ClientState, ClientStateService};
+
+    fn setup() -> (MockServer, Arc<Repository<ClientState>>, ClientStateService) {
+        let broker = MockServer::start();
+        let connection_pool = Pool::new(SqliteConnectionManager::memory()).unwrap();
+        let repo = Repository::<ClientState>::init(
+            &connection_pool,
+            "client_states",
+            |client_state| client_state.client_id.clone(),
+            "clientId",
+        )
+        .unwrap();
+        let repo = Arc::new(repo);
+        let service =
+            ClientStateService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
+        (broker, repo, service)
+    }
+
+    fn build_state(policy_id: &str, state_name: &str) -> FsmStateInformationItem {
+        FsmStateInformationItem {
+            arguments: None,
+            behavior_id: Some("Mqtt.events".to_string()),
+            first_set_at: Some("2024-01-01T00:00:00Z".to_string()),
+            policy_id: Some(policy_id.to_string()),
+            state_name: Some(state_name.to_string()),
+            state_type: Some("INTERMEDIATE".to_string()),
+            variables: None,
+        }
+    }
+
+    fn mock_client_state(
+        broker: &MockServer,
+        client_id: &str,
+        states: Vec<FsmStateInformationItem>,
+    ) {
+        let response = FsmStatesInformationListItem {
+            items: Some(states),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path(format!(
+                "/api/v1/data-hub/behavior-validation/states/{client_id}"
+            ));
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&response).unwrap());
+        });
+    }
+
+    #[tokio::test]
+    async fn test_load_client_state() {
+        let (broker, repo, service) = setup();
+        let state = build_state("policy-1", "Connected");
+        mock_client_state(&broker, "client-1", vec![state.clone()]);
+
+        service.load_client_state("client-1").await.unwrap();
+
+        let client_state = repo.find_by_id("client-1").unwrap();
+        assert_eq!(vec![state], client_state.states);
+    }
+
+    #[tokio::test]
+    async fn test_load_client_state_error() {
+        let (broker, repo, service) = setup();
+        let error = GetClientStateError::Status404(Errors::new());
+        broker.mock(|when, then| {
+            when.method(GET);
+            then.status(404)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let result = service.load_client_state("client-1").await;
+
+        assert!(result.is_err());
+        assert!(repo.find_by_id("client-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_clients_in_state() {
+        let (broker, _repo, service) = setup();
+        mock_client_state(
+            &broker,
+            "client-1",
+            vec![build_state("policy-1", "Connected")],
+        );
+        mock_client_state(
+            &broker,
+            "client-2",
+            vec![build_state("policy-1", "Disconnected")],
+        );
+        mock_client_state(
+            &broker,
+            "client-3",
+            vec![build_state("policy-2", "Connected")],
+        );
+        let client_ids = vec![
+            "client-1".to_string(),
+            "client-2".to_string(),
+            "client-3".to_string(),
+            "client-4".to_string(),
+        ];
+
+        let in_state = service
+            .find_clients_in_state(client_ids.clone(), "policy-1", Some("Connected"), 2)
+            .await
+            .unwrap();
+        let in_policy = service
+            .find_clients_in_state(client_ids, "policy-1", None, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(vec!["client-1"], in_state);
+        assert_eq!(vec!["client-1", "client-2"], in_policy);
+    }
+}
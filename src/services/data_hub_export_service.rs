@@ -0,0 +1,593 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use hivemq_openapi::models::{BehaviorPolicy, DataPolicy, Schema, Script};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::repository::Repository;
+use crate::services::behavior_policy_service::BehaviorPolicyService;
+use crate::services::data_policy_service::DataPolicyService;
+use crate::services::schema_service::SchemaService;
+use crate::services::scripts_service::ScriptService;
+use crate::sqlite::init_sqlite;
+
+pub const SCHEMAS_DIR: &str = "schemas";
+pub const SCRIPTS_DIR: &str = "scripts";
+pub const DATA_POLICIES_DIR: &str = "data-policies";
+pub const BEHAVIOR_POLICIES_DIR: &str = "behavior-policies";
+
+/// The files of the directory of a schema or script. Their names do not depend on the id,
+/// so ids with dots, e.g. `foo.schema`, can not collide with the files of other items.
+const ITEM_FILE: &str = "item.json";
+const SCHEMA_JSON_FILE: &str = "definition.json";
+const SCHEMA_DESC_FILE: &str = "definition.desc";
+const SCRIPT_SOURCE_FILE: &str = "source.js";
+const ITEM_DIR_FILES: [&str; 4] = [
+    ITEM_FILE,
+    SCHEMA_JSON_FILE,
+    SCHEMA_DESC_FILE,
+    SCRIPT_SOURCE_FILE,
+];
+
+/// Fields that are set by the broker. They are not exported, so an unchanged
+/// configuration exports to the same files.
+const GENERATED_FIELDS: [&str; 3] = ["createdAt", "lastUpdatedAt", "version"];
+
+/// The number of items written by an export.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ExportSummary {
+    pub schemas: usize,
+    pub scripts: usize,
+    #[serde(rename = "dataPolicies")]
+    pub data_policies: usize,
+    #[serde(rename = "behaviorPolicies")]
+    pub behavior_policies: usize,
+}
+
+impl fmt::Display for ExportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} schemas, {} scripts, {} data policies and {} behavior policies",
+            self.schemas, self.scripts, self.data_policies, self.behavior_policies
+        )
+    }
+}
+
+/// All Data Hub items of a broker or of an exported directory.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataHubConfig {
+    pub schemas: Vec<Schema>,
+    pub scripts: Vec<Script>,
+    pub data_policies: Vec<DataPolicy>,
+    pub behavior_policies: Vec<BehaviorPolicy>,
+}
+
+impl DataHubConfig {
+    /// Reads a directory in the layout written by [DataHubExportService::export].
+    /// A missing subdirectory is read as no items of that kind.
+    /// Plans leave these kinds unchanged on the broker.
+    pub fn read(directory: &Path) -> Result<Self, String> {
+        let dirs = [
+            SCHEMAS_DIR,
+            SCRIPTS_DIR,
+            DATA_POLICIES_DIR,
+            BEHAVIOR_POLICIES_DIR,
+        ];
+        if !dirs.iter().any(|dir| directory.join(dir).is_dir()) {
+            return Err(format!(
+                "{} does not contain a Data Hub configuration",
+                directory.display()
+            ));
+        }
+
+        let mut schemas = Vec::new();
+        for (path, mut metadata) in read_item_dirs(&directory.join(SCHEMAS_DIR))? {
+            let json = path.with_file_name(SCHEMA_JSON_FILE);
+            let definition = if json.exists() {
+                json
+            } else {
+                path.with_file_name(SCHEMA_DESC_FILE)
+            };
+            metadata["schemaDefinition"] =
+                Value::String(BASE64_STANDARD.encode(read(&definition)?));
+            schemas.push(from_value(&path, metadata)?);
+        }
+
+        let mut scripts = Vec::new();
+        for (path, mut metadata) in read_item_dirs(&directory.join(SCRIPTS_DIR))? {
+            let source = path.with_file_name(SCRIPT_SOURCE_FILE);
+            metadata["source"] = Value::String(BASE64_STANDARD.encode(read(&source)?));
+            scripts.push(from_value(&path, metadata)?);
+        }
+
+        let mut data_policies = Vec::new();
+        for (path, metadata) in read_metadata(&directory.join(DATA_POLICIES_DIR))? {
+            data_policies.push(from_value(&path, metadata)?);
+        }
+
+        let mut behavior_policies = Vec::new();
+        for (path, metadata) in read_metadata(&directory.join(BEHAVIOR_POLICIES_DIR))? {
+            behavior_policies.push(from_value(&path, metadata)?);
+        }
+
+        Ok(DataHubConfig {
+            schemas,
+            scripts,
+            data_policies,
+            behavior_policies,
+        })
+    }
+
+    pub fn summary(&self) -> ExportSummary {
+        ExportSummary {
+            schemas: self.schemas.len(),
+            scripts: self.scripts.len(),
+            data_policies: self.data_policies.len(),
+            behavior_policies: self.behavior_policies.len(),
+        }
+    }
+}
+
+/// The services of all Data Hub items, sharing a cache of their own.
+pub struct DataHubServices {
+    pub schema_service: SchemaService,
+    pub script_service: ScriptService,
+    pub data_policy_service: DataPolicyService,
+    pub behavior_policy_service: BehaviorPolicyService,
+    schema_repository: Arc<Repository<Schema>>,
+    script_repository: Arc<Repository<Script>>,
+    data_policy_repository: Arc<Repository<DataPolicy>>,
+    behavior_policy_repository: Arc<Repository<BehaviorPolicy>>,
+}
+
+impl DataHubServices {
+    pub fn new(connection: &HivemqConnection) -> Self {
+        let sqlite_pool = init_sqlite();
+        let schema_repository = Arc::new(
+            Repository::<Schema>::init(&sqlite_pool, "schemas", |val| val.id.clone(), "id")
+                .unwrap(),
+        );
+        let script_repository = Arc::new(
+            Repository::<Script>::init(&sqlite_pool, "scripts", |val| val.id.clone(), "id")
+                .unwrap(),
+        );
+        let data_policy_repository = Arc::new(
+            Repository::<DataPolicy>::init(
+                &sqlite_pool,
+                "data_policies",
+                |val| val.id.clone(),
+                "id",
+            )
+            .unwrap(),
+        );
+        let behavior_policy_repository = Arc::new(
+            Repository::<BehaviorPolicy>::init(
+                &sqlite_pool,
+                "behavior_policies",
+                |val| val.id.clone(),
+                "id",
+            )
+            .unwrap(),
+        );
+
+        DataHubServices {
+            schema_service: SchemaService::new(schema_repository.clone(), connection),
+            script_service: ScriptService::new(script_repository.clone(), connection),
+            data_policy_service: DataPolicyService::new(data_policy_repository.clone(), connection),
+            behavior_policy_service: BehaviorPolicyService::new(
+                behavior_policy_repository.clone(),
+                connection,
+            ),
+            schema_repository,
+            script_repository,
+            data_policy_repository,
+            behavior_policy_repository,
+        }
+    }
+
+    /// Loads all items from the broker.
+    pub async fn load_config(&self) -> Result<DataHubConfig, String> {
+        self.schema_service.load_schemas().await?;
+        self.script_service.load_scripts().await?;
+        self.data_policy_service.load_data_policies().await?;
+        self.behavior_policy_service
+            .load_behavior_policies()
+            .await?;
+
+        Ok(DataHubConfig {
+            schemas: find_all(&self.schema_repository)?,
+            scripts: find_all(&self.script_repository)?,
+            data_policies: find_all(&self.data_policy_repository)?,
+            behavior_policies: find_all(&self.behavior_policy_repository)?,
+        })
+    }
+}
+
+/// Exports the Data Hub configuration of a broker into a directory with one file per item:
+///
+/// - `schemas/<id>/item.json` and the decoded definition `schemas/<id>/definition.json` or `schemas/<id>/definition.desc`
+/// - `scripts/<id>/item.json` and the decoded source `scripts/<id>/source.js`
+/// - `data-policies/<id>.json`
+/// - `behavior-policies/<id>.json`
+pub struct DataHubExportService {
+    services: DataHubServices,
+}
+
+impl DataHubExportService {
+    pub fn new(connection: &HivemqConnection) -> Self {
+        DataHubExportService {
+            services: DataHubServices::new(connection),
+        }
+    }
+
+    /// Writes the configuration into the directory.
+    /// Previously exported files are replaced, so items deleted on the broker are removed.
+    /// Other files in the directory are kept.
+    pub async fn export(&self, directory: &Path) -> Result<ExportSummary, String> {
+        let config = self.services.load_config().await?;
+
+        let schemas_dir = clear_dir(directory.join(SCHEMAS_DIR))?;
+        for schema in &config.schemas {
+            let definition = decode(&schema.schema_definition).map_err(|err| {
+                format!("Schema '{}' has an invalid definition: {err}", schema.id)
+            })?;
+            let definition_file = if schema.r#type.eq_ignore_ascii_case("PROTOBUF") {
+                SCHEMA_DESC_FILE
+            } else {
+                SCHEMA_JSON_FILE
+            };
+            let schema_dir = create_dir(schemas_dir.join(file_name(&schema.id)))?;
+            write(&schema_dir.join(definition_file), &definition)?;
+            write_metadata(&schema_dir.join(ITEM_FILE), schema, &["schemaDefinition"])?;
+        }
+
+        let scripts_dir = clear_dir(directory.join(SCRIPTS_DIR))?;
+        for script in &config.scripts {
+            let source = decode(&script.source)
+                .map_err(|err| format!("Script '{}' has an invalid source: {err}", script.id))?;
+            let script_dir = create_dir(scripts_dir.join(file_name(&script.id)))?;
+            write(&script_dir.join(SCRIPT_SOURCE_FILE), &source)?;
+            write_metadata(&script_dir.join(ITEM_FILE), script, &["source"])?;
+        }
+
+        let data_policies_dir = clear_dir(directory.join(DATA_POLICIES_DIR))?;
+        for data_policy in &config.data_policies {
+            write_metadata(
+                &json_file(&data_policies_dir, &data_policy.id),
+                data_policy,
+                &[],
+            )?;
+        }
+
+        let behavior_policies_dir = clear_dir(directory.join(BEHAVIOR_POLICIES_DIR))?;
+        for behavior_policy in &config.behavior_policies {
+            write_metadata(
+                &json_file(&behavior_policies_dir, &behavior_policy.id),
+                behavior_policy,
+                &[],
+            )?;
+        }
+
+        Ok(config.summary())
+    }
+}
+
+fn find_all<T: Serialize + DeserializeOwned>(repository: &Repository<T>) -> Result<Vec<T>, String> {
+    repository.find_all().map_err(|err| format!("{err:?}"))
+}
+
+pub fn decode(base64: &str) -> Result<Vec<u8>, String> {
+    BASE64_STANDARD
+        .decode(base64)
+        .map_err(|err| err.to_string())
+}
+
+/// Replaces characters that are not allowed in file names.
+pub fn file_name(id: &str) -> String {
+    id.replace(['/', '\\'], "_")
+}
+
+/// Returns the item as JSON without the generated and the given fields.
+pub fn metadata<T: Serialize>(item: &T, excluded_fields: &[&str]) -> Result<Value, String> {
+    let mut value = serde_json::to_value(item).map_err(|err| err.to_string())?;
+    if let Value::Object(fields) = &mut value {
+        for field in GENERATED_FIELDS.iter().chain(excluded_fields) {
+            fields.remove(*field);
+        }
+    }
+    Ok(value)
+}
+
+/// Creates the directory or removes the files a previous export wrote into it:
+/// the `*.json` files and the files of the item directories of schemas and scripts.
+/// Other files are kept, so exporting into a directory does not delete unrelated files.
+fn clear_dir(directory: PathBuf) -> Result<PathBuf, String> {
+    for path in list_dir(&directory)? {
+        if path.is_dir() {
+            for file in ITEM_DIR_FILES {
+                remove_file(&path.join(file))?;
+            }
+            // Fails if other files are left in it, which are kept then
+            let _ = std::fs::remove_dir(&path);
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            remove_file(&path)?;
+        }
+    }
+    create_dir(directory)
+}
+
+fn create_dir(directory: PathBuf) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(&directory)
+        .map_err(|err| format!("Creating {} failed: {err}", directory.display()))?;
+    Ok(directory)
+}
+
+fn remove_file(path: &Path) -> Result<(), String> {
+    if path.is_file() {
+        std::fs::remove_file(path)
+            .map_err(|err| format!("Removing {} failed: {err}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Returns the paths of the entries of the directory, ordered by their name.
+/// A missing directory has no entries.
+fn list_dir(directory: &Path) -> Result<Vec<PathBuf>, String> {
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(directory)
+        .map_err(|err| format!("Reading {} failed: {err}", directory.display()))?;
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("Reading {} failed: {err}", directory.display()))?
+            .path();
+        paths.push(path);
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn write(path: &Path, content: &[u8]) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|err| format!("Writing {} failed: {err}", path.display()))
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("Reading {} failed: {err}", path.display()))
+}
+
+/// Returns the path of the file `<id>.json` of a policy.
+fn json_file(directory: &Path, id: &str) -> PathBuf {
+    directory.join(format!("{}.json", file_name(id)))
+}
+
+/// Writes the item without the generated and the given fields into the file.
+fn write_metadata<T: Serialize>(
+    path: &Path,
+    item: &T,
+    excluded_fields: &[&str],
+) -> Result<(), String> {
+    let value = metadata(item, excluded_fields)?;
+    let mut json = serde_json::to_string_pretty(&value).map_err(|err| err.to_string())?;
+    json.push('\n');
+    write(path, json.as_bytes())
+}
+
+/// Reads all `<id>.json` files of the directory, ordered by their name.
+fn read_metadata(directory: &Path) -> Result<Vec<(PathBuf, Value)>, String> {
+    list_dir(directory)?
+        .into_iter()
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .map(read_json)
+        .collect()
+}
+
+/// Reads the `item.json` files of the item directories of schemas or scripts, ordered by their name.
+/// Directories without it are skipped.
+fn read_item_dirs(directory: &Path) -> Result<Vec<(PathBuf, Value)>, String> {
+    list_dir(directory)?
+        .into_iter()
+        .map(|path| path.join(ITEM_FILE))
+        .filter(|path| path.is_file())
+        .map(read_json)
+        .collect()
+}
+
+fn read_json(path: PathBuf) -> Result<(PathBuf, Value), String> {
+    let value = serde_json::from_slice(&read(&path)?)
+        .map_err(|err| format!("{} is not valid JSON: {err}", path.display()))?;
+    Ok((path, value))
+}
+
+fn from_value<T: DeserializeOwned>(path: &Path, value: Value) -> Result<T, String> {
+    serde_json::from_value(value).map_err(|err| format!("{} is invalid: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use hivemq_openapi::models::script::FunctionType;
+    use hivemq_openapi::models::{
+        BehaviorPolicy, BehaviorPolicyBehavior, BehaviorPolicyList, BehaviorPolicyMatching,
+        DataPolicy, DataPolicyList, DataPolicyMatching, Schema, SchemaList, Script, ScriptList,
+    };
+    use httpmock::Method::GET;
+    use httpmock::MockServer;
+    use pretty_assertions::assert_eq;
+    use serde::Serialize;
+
+    use crate::hivemq_rest_client::HivemqConnection;
+    use crate::services::data_hub_export_service::{
+        DataHubConfig, DataHubExportService, ExportSummary,
+    };
+
+    fn mock_list<T: Serialize>(broker: &MockServer, path: &str, list: &T) {
+        broker.mock(|when, then| {
+            when.method(GET).path(path);
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(list).unwrap());
+        });
+    }
+
+    #[tokio::test]
+    async fn test_export() {
+        let broker = MockServer::start();
+        let mut schema = Schema::new(
+            "schema-1".to_string(),
+            BASE64_STANDARD.encode("{\"type\":\"object\"}"),
+            "JSON".to_string(),
+        );
+        schema.created_at = Some("2024-01-01T00:00:00Z".to_string());
+        schema.version = Some(2);
+        let dotted_schema = Schema::new(
+            "schema-1.schema".to_string(),
+            BASE64_STANDARD.encode("{}"),
+            "JSON".to_string(),
+        );
+        let script = Script::new(
+            FunctionType::Transformation,
+            "script-1".to_string(),
+            BASE64_STANDARD.encode("function transform() {}"),
+        );
+        let data_policy = DataPolicy::new(
+            "data-policy-1".to_string(),
+            DataPolicyMatching::new("topic/#".to_string()),
+        );
+        let behavior_policy = BehaviorPolicy::new(
+            BehaviorPolicyBehavior::new("Mqtt.events".to_string()),
+            "behavior-policy-1".to_string(),
+            BehaviorPolicyMatching::new("client-.*".to_string()),
+        );
+        mock_list(
+            &broker,
+            "/api/v1/data-hub/schemas",
+            &SchemaList {
+                _links: None,
+                items: Some(vec![schema, dotted_schema]),
+            },
+        );
+        mock_list(
+            &broker,
+            "/api/v1/data-hub/scripts",
+            &ScriptList {
+                _links: None,
+                items: Some(vec![script]),
+            },
+        );
+        mock_list(
+            &broker,
+            "/api/v1/data-hub/data-validation/policies",
+            &DataPolicyList {
+                _links: None,
+                items: Some(vec![data_policy.clone()]),
+            },
+        );
+        mock_list(
+            &broker,
+            "/api/v1/data-hub/behavior-validation/policies",
+            &BehaviorPolicyList {
+                _links: None,
+                items: Some(vec![behavior_policy]),
+            },
+        );
+        let tempdir = tempfile::tempdir().unwrap();
+        let directory = tempdir.path();
+        std::fs::create_dir_all(directory.join("schemas/deleted")).unwrap();
+        std::fs::create_dir_all(directory.join("schemas/other")).unwrap();
+        std::fs::write(directory.join("schemas/deleted/item.json"), "{}").unwrap();
+        std::fs::write(directory.join("schemas/other/notes.txt"), "").unwrap();
+        std::fs::write(directory.join("schemas/README.md"), "").unwrap();
+        let service = DataHubExportService::new(&HivemqConnection::new(broker.base_url()));
+
+        let summary = service.export(directory).await.unwrap();
+
+        assert_eq!(
+            ExportSummary {
+                schemas: 2,
+                scripts: 1,
+                data_policies: 1,
+                behavior_policies: 1,
+            },
+            summary
+        );
+        let read = |path: &str| std::fs::read_to_string(directory.join(path)).unwrap();
+        assert_eq!(
+            "{\"type\":\"object\"}",
+            read("schemas/schema-1/definition.json")
+        );
+        assert_eq!(
+            "{\n  \"type\": \"JSON\",\n  \"id\": \"schema-1\"\n}\n",
+            read("schemas/schema-1/item.json")
+        );
+        assert_eq!("{}", read("schemas/schema-1.schema/definition.json"));
+        assert_eq!(
+            "function transform() {}",
+            read("scripts/script-1/source.js")
+        );
+        assert!(!read("scripts/script-1/item.json").contains("source"));
+        assert_eq!(
+            data_policy,
+            serde_json::from_str(&read("data-policies/data-policy-1.json")).unwrap()
+        );
+        assert!(directory
+            .join("behavior-policies/behavior-policy-1.json")
+            .exists());
+        assert!(!directory.join("schemas/deleted").exists());
+        assert!(directory.join("schemas/other/notes.txt").exists());
+        assert!(directory.join("schemas/README.md").exists());
+
+        let config = DataHubConfig::read(directory).unwrap();
+        assert_eq!(
+            BASE64_STANDARD.encode("{\"type\":\"object\"}"),
+            config.schemas[0].schema_definition
+        );
+        assert_eq!("schema-1.schema", config.schemas[1].id);
+        assert_eq!(
+            BASE64_STANDARD.encode("{}"),
+            config.schemas[1].schema_definition
+        );
+        assert_eq!(
+            BASE64_STANDARD.encode("function transform() {}"),
+            config.scripts[0].source
+        );
+        assert_eq!(vec![data_policy], config.data_policies);
+        assert_eq!(summary, config.summary());
+    }
+
+    #[test]
+    fn test_read_without_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let result = DataHubConfig::read(tempdir.path());
+
+        assert!(result
+            .unwrap_err()
+            .contains("does not contain a Data Hub configuration"));
+    }
+
+    #[tokio::test]
+    async fn test_export_error() {
+        let broker = MockServer::start();
+        broker.mock(|when, then| {
+            when.method(GET);
+            then.status(503);
+        });
+        let service = DataHubExportService::new(&HivemqConnection::new(broker.base_url()));
+
+        let tempdir = tempfile::tempdir().unwrap();
+        let result = service.export(tempdir.path()).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use hivemq_openapi::apis::configuration::Configuration;
+use hivemq_openapi::apis::data_hub_management_api::start_trial_mode;
+
+use crate::hivemq_rest_client;
+use crate::hivemq_rest_client::{transform_api_err, HivemqConnection};
+
+pub struct DataHubService {
+    config: Configuration,
+}
+
+impl DataHubService {
+    pub fn new(connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
+        DataHubService { config }
+    }
+
+    /// Starts the Data Hub trial mode, which enables Data Hub without a license for a limited time.
+    pub async fn start_trial_mode(&self) -> Result<(), String> {
+        start_trial_mode(&self.config)
+            .await
+            .map_err(transform_api_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hivemq_openapi::apis::data_hub_management_api::StartTrialModeError;
+    use hivemq_openapi::models::Errors;
+    use httpmock::Method::POST;
+    use httpmock::MockServer;
+
+    use crate::hivemq_rest_client::HivemqConnection;
+    use crate::services::data_hub_service::DataHubService;
+
+    #[tokio::test]
+    async fn test_start_trial_mode() {
+        let broker = MockServer::start();
+        let service = DataHubService::new(&HivemqConnection::new(broker.base_url()));
+        let trial_mock = broker.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/data-hub/management/start-trial");
+            then.status(204);
+        });
+
+        let result = service.start_trial_mode().await;
+
+        trial_mock.assert();
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_start_trial_mode_error() {
+        let broker = MockServer::start();
+        let service = DataHubService::new(&HivemqConnection::new(broker.base_url()));
+        let error = StartTrialModeError::Status400(Errors::new());
+        broker.mock(|when, then| {
+            when.method(POST);
+            then.status(400)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let result = service.start_trial_mode().await;
+
+        assert!(result.is_err());
+    }
+}
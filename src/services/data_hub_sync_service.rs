@@ -0,0 +1,554 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::hivemq_rest_client::HivemqConnection;
+use crate::references::Reference;
+use crate::services::data_hub_export_service::{
+    decode, metadata, DataHubConfig, DataHubServices, BEHAVIOR_POLICIES_DIR, DATA_POLICIES_DIR,
+    SCHEMAS_DIR, SCRIPTS_DIR,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemType {
+    Schema,
+    Script,
+    DataPolicy,
+    BehaviorPolicy,
+}
+
+impl fmt::Display for ItemType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemType::Schema => write!(f, "Schema"),
+            ItemType::Script => write!(f, "Script"),
+            ItemType::DataPolicy => write!(f, "Data Policy"),
+            ItemType::BehaviorPolicy => write!(f, "Behavior Policy"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Change {
+    Create,
+    /// Schemas and scripts are updated by creating a new version
+    Update,
+    Delete,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Create => write!(f, "Create"),
+            Change::Update => write!(f, "Update"),
+            Change::Delete => write!(f, "Delete"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PlannedChange {
+    pub change: Change,
+    #[serde(rename = "type")]
+    pub item_type: ItemType,
+    pub id: String,
+    /// The item on the broker as shown in the diff
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    /// The item of the directory as shown in the diff
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired: Option<String>,
+    /// The JSON that is sent to the broker
+    #[serde(skip)]
+    item: Option<String>,
+}
+
+/// The changes that make the broker match a Data Hub configuration,
+/// ordered so that items are created before the policies referencing them
+/// and deleted after them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Plan {
+    pub changes: Vec<PlannedChange>,
+}
+
+impl Plan {
+    pub fn new(current: &DataHubConfig, desired: &DataHubConfig) -> Result<Self, String> {
+        let (schemas, deleted_schemas) = plan_items(
+            ItemType::Schema,
+            &current.schemas,
+            &desired.schemas,
+            |schema| &schema.id,
+            Some("schemaDefinition"),
+        )?;
+        let (scripts, deleted_scripts) = plan_items(
+            ItemType::Script,
+            &current.scripts,
+            &desired.scripts,
+            |script| &script.id,
+            Some("source"),
+        )?;
+        let (data_policies, deleted_data_policies) = plan_items(
+            ItemType::DataPolicy,
+            &current.data_policies,
+            &desired.data_policies,
+            |policy| &policy.id,
+            None,
+        )?;
+        let (behavior_policies, deleted_behavior_policies) = plan_items(
+            ItemType::BehaviorPolicy,
+            &current.behavior_policies,
+            &desired.behavior_policies,
+            |policy| &policy.id,
+            None,
+        )?;
+
+        let changes = [
+            deleted_behavior_policies,
+            deleted_data_policies,
+            schemas,
+            scripts,
+            data_policies,
+            behavior_policies,
+            deleted_scripts,
+            deleted_schemas,
+        ]
+        .concat();
+        Ok(Plan { changes })
+    }
+
+    /// Deletes an item after the policies that reference it,
+    /// the behavior policies before the data policies.
+    pub fn delete_cascade(item_type: ItemType, id: &str, references: &[Reference]) -> Self {
+        let mut policies: Vec<(ItemType, &str)> = references
+            .iter()
+            .filter(|reference| reference.item_type == item_type && reference.item_id == id)
+            .map(|reference| (reference.policy_type, reference.policy_id.as_str()))
+            .collect();
+        policies.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
+        policies.dedup();
+
+        let changes = policies
+            .into_iter()
+            .chain([(item_type, id)])
+            .map(|(item_type, id)| PlannedChange {
+                change: Change::Delete,
+                item_type,
+                id: id.to_string(),
+                current: None,
+                desired: None,
+                item: None,
+            })
+            .collect();
+        Plan { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn count(&self, change: Change) -> usize {
+        self.changes
+            .iter()
+            .filter(|planned| planned.change == change)
+            .count()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} to create, {} to update and {} to delete",
+            self.count(Change::Create),
+            self.count(Change::Update),
+            self.count(Change::Delete)
+        )
+    }
+}
+
+/// Compares a directory in the layout of the Data Hub export with the broker
+/// and applies the differences.
+pub struct DataHubSyncService {
+    services: DataHubServices,
+}
+
+impl DataHubSyncService {
+    pub fn new(connection: &HivemqConnection) -> Self {
+        DataHubSyncService {
+            services: DataHubServices::new(connection),
+        }
+    }
+
+    /// Plans the changes of the kinds of items that have a subdirectory in the directory.
+    /// The other kinds are left as they are on the broker.
+    pub async fn plan(&self, directory: &Path) -> Result<Plan, String> {
+        let desired = DataHubConfig::read(directory)?;
+        let current = self.services.load_config().await?;
+        let desired = keep_missing_kinds(directory, &current, desired);
+        Plan::new(&current, &desired)
+    }
+
+    /// Applies the changes in the order of the plan and stops at the first failure.
+    /// Returns the number of applied changes.
+    pub async fn apply(&self, plan: &Plan) -> Result<usize, String> {
+        for (index, planned) in plan.changes.iter().enumerate() {
+            self.apply_change(planned).await.map_err(|err| {
+                format!(
+                    "Applied {index} of {} changes. {} {} '{}' failed: {err}",
+                    plan.changes.len(),
+                    planned.change,
+                    planned.item_type,
+                    planned.id
+                )
+            })?;
+        }
+        Ok(plan.changes.len())
+    }
+
+    async fn apply_change(&self, planned: &PlannedChange) -> Result<String, String> {
+        let services = &self.services;
+        let id = planned.id.as_str();
+        let item = planned.item.clone().unwrap_or_default();
+        match (planned.item_type, planned.change) {
+            (ItemType::Schema, Change::Delete) => services.schema_service.delete_schema(id).await,
+            (ItemType::Schema, _) => services.schema_service.create_schema(&item).await,
+            (ItemType::Script, Change::Delete) => services.script_service.delete_script(id).await,
+            (ItemType::Script, _) => services.script_service.create_script(&item).await,
+            (ItemType::DataPolicy, Change::Create) => {
+                services.data_policy_service.create_data_policy(&item).await
+            }
+            (ItemType::DataPolicy, Change::Update) => {
+                services.data_policy_service.update_data_policy(&item).await
+            }
+            (ItemType::DataPolicy, Change::Delete) => {
+                services.data_policy_service.delete_data_policy(id).await
+            }
+            (ItemType::BehaviorPolicy, Change::Create) => {
+                services
+                    .behavior_policy_service
+                    .create_behavior_policy(&item)
+                    .await
+            }
+            (ItemType::BehaviorPolicy, Change::Update) => {
+                services
+                    .behavior_policy_service
+                    .update_behavior_policy(&item)
+                    .await
+            }
+            (ItemType::BehaviorPolicy, Change::Delete) => {
+                services
+                    .behavior_policy_service
+                    .delete_behavior_policy(id)
+                    .await
+            }
+        }
+    }
+}
+
+/// Replaces the items of the kinds without a subdirectory with the current items,
+/// so a directory with only some kinds does not delete the others.
+fn keep_missing_kinds(
+    directory: &Path,
+    current: &DataHubConfig,
+    mut desired: DataHubConfig,
+) -> DataHubConfig {
+    let missing = |dir: &str| !directory.join(dir).is_dir();
+    if missing(SCHEMAS_DIR) {
+        desired.schemas = current.schemas.clone();
+    }
+    if missing(SCRIPTS_DIR) {
+        desired.scripts = current.scripts.clone();
+    }
+    if missing(DATA_POLICIES_DIR) {
+        desired.data_policies = current.data_policies.clone();
+    }
+    if missing(BEHAVIOR_POLICIES_DIR) {
+        desired.behavior_policies = current.behavior_policies.clone();
+    }
+    desired
+}
+
+/// Returns the creates and updates ordered like the desired items and the deletes ordered by id.
+fn plan_items<T: Serialize>(
+    item_type: ItemType,
+    current: &[T],
+    desired: &[T],
+    id_fn: fn(&T) -> &String,
+    content_field: Option<&str>,
+) -> Result<(Vec<PlannedChange>, Vec<PlannedChange>), String> {
+    let mut current_documents = HashMap::new();
+    for item in current {
+        current_documents.insert(id_fn(item).clone(), document(item, content_field)?);
+    }
+
+    let mut upserts = Vec::new();
+    for item in desired {
+        let id = id_fn(item);
+        let desired_document = document(item, content_field)?;
+        let change = match current_documents.remove(id) {
+            None => Change::Create,
+            Some(current_document) if current_document != desired_document => Change::Update,
+            Some(_) => continue,
+        };
+        let current = current
+            .iter()
+            .find(|current| id_fn(current) == id)
+            .map(|current| document(current, content_field))
+            .transpose()?;
+        upserts.push(PlannedChange {
+            change,
+            item_type,
+            id: id.clone(),
+            current,
+            desired: Some(desired_document),
+            item: Some(serde_json::to_string(item).map_err(|err| err.to_string())?),
+        });
+    }
+
+    let mut deletes: Vec<PlannedChange> = current_documents
+        .into_iter()
+        .map(|(id, current_document)| PlannedChange {
+            change: Change::Delete,
+            item_type,
+            id,
+            current: Some(current_document),
+            desired: None,
+            item: None,
+        })
+        .collect();
+    deletes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok((upserts, deletes))
+}
+
+/// Formats the item as pretty JSON without the generated fields.
+/// The base64 encoded content field is decoded and appended, so changes of it can be read in a diff.
+fn document<T: Serialize>(item: &T, content_field: Option<&str>) -> Result<String, String> {
+    let excluded_fields: Vec<&str> = content_field.into_iter().collect();
+    let value = metadata(item, &excluded_fields)?;
+    let mut document = serde_json::to_string_pretty(&value).map_err(|err| err.to_string())?;
+    document.push('\n');
+
+    if let Some(field) = content_field {
+        let item = serde_json::to_value(item).map_err(|err| err.to_string())?;
+        let encoded = item.get(field).and_then(Value::as_str).unwrap_or_default();
+        let content = match decode(encoded).map(String::from_utf8) {
+            Ok(Ok(content)) => content,
+            _ => encoded.to_string(),
+        };
+        document.push('\n');
+        document.push_str(&content);
+        if !content.ends_with('\n') {
+            document.push('\n');
+        }
+    }
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+    use hivemq_openapi::models::{
+        BehaviorPolicy, BehaviorPolicyBehavior, BehaviorPolicyMatching, DataPolicy,
+        DataPolicyMatching, Schema,
+    };
+    use httpmock::Method::{DELETE, POST, PUT};
+    use httpmock::MockServer;
+    use pretty_assertions::assert_eq;
+
+    use crate::hivemq_rest_client::HivemqConnection;
+    use crate::references::Reference;
+    use crate::services::data_hub_export_service::DataHubConfig;
+    use crate::services::data_hub_sync_service::{
+        keep_missing_kinds, Change, DataHubSyncService, ItemType, Plan,
+    };
+
+    fn schema(id: &str, definition: &str) -> Schema {
+        Schema::new(
+            id.to_string(),
+            BASE64_STANDARD.encode(definition),
+            "JSON".to_string(),
+        )
+    }
+
+    fn data_policy(id: &str, topic_filter: &str) -> DataPolicy {
+        DataPolicy::new(
+            id.to_string(),
+            DataPolicyMatching::new(topic_filter.to_string()),
+        )
+    }
+
+    fn behavior_policy(id: &str) -> BehaviorPolicy {
+        BehaviorPolicy::new(
+            BehaviorPolicyBehavior::new("Mqtt.events".to_string()),
+            id.to_string(),
+            BehaviorPolicyMatching::new(".*".to_string()),
+        )
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut unchanged = schema("unchanged", "{}");
+        let current = DataHubConfig {
+            schemas: vec![schema("updated", "{}"), unchanged.clone()],
+            behavior_policies: vec![behavior_policy("deleted")],
+            ..DataHubConfig::default()
+        };
+        unchanged.version = Some(3);
+        let desired = DataHubConfig {
+            schemas: vec![schema("updated", "{\"type\":\"object\"}"), unchanged],
+            data_policies: vec![data_policy("created", "topic/#")],
+            ..DataHubConfig::default()
+        };
+
+        let plan = Plan::new(&current, &desired).unwrap();
+
+        let changes: Vec<(Change, ItemType, &str)> = plan
+            .changes
+            .iter()
+            .map(|planned| (planned.change, planned.item_type, planned.id.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Change::Delete, ItemType::BehaviorPolicy, "deleted"),
+                (Change::Update, ItemType::Schema, "updated"),
+                (Change::Create, ItemType::DataPolicy, "created"),
+            ],
+            changes
+        );
+        assert_eq!(
+            Some("{\n  \"id\": \"updated\",\n  \"type\": \"JSON\"\n}\n\n{\"type\":\"object\"}\n"),
+            plan.changes[1].desired.as_deref()
+        );
+        assert_eq!("1 to create, 1 to update and 1 to delete", plan.to_string());
+    }
+
+    #[test]
+    fn test_plan_keeps_missing_kinds() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let directory = tempdir.path();
+        std::fs::create_dir_all(directory.join("schemas")).unwrap();
+        let current = DataHubConfig {
+            schemas: vec![schema("deleted", "{}")],
+            data_policies: vec![data_policy("kept", "topic/#")],
+            ..DataHubConfig::default()
+        };
+        let desired = DataHubConfig::read(directory).unwrap();
+
+        let desired = keep_missing_kinds(directory, &current, desired);
+        let plan = Plan::new(&current, &desired).unwrap();
+
+        let changes: Vec<(Change, ItemType, &str)> = plan
+            .changes
+            .iter()
+            .map(|planned| (planned.change, planned.item_type, planned.id.as_str()))
+            .collect();
+        assert_eq!(vec![(Change::Delete, ItemType::Schema, "deleted")], changes);
+    }
+
+    #[test]
+    fn test_delete_cascade() {
+        let reference = |policy_type, policy_id: &str, version: &str| Reference {
+            policy_type,
+            policy_id: policy_id.to_string(),
+            item_type: ItemType::Script,
+            item_id: "script-1".to_string(),
+            version: version.to_string(),
+        };
+        let references = vec![
+            reference(ItemType::DataPolicy, "policy-b", "latest"),
+            reference(ItemType::DataPolicy, "policy-a", "1"),
+            reference(ItemType::DataPolicy, "policy-a", "2"),
+            reference(ItemType::BehaviorPolicy, "policy-c", "latest"),
+        ];
+
+        let plan = Plan::delete_cascade(ItemType::Script, "script-1", &references);
+
+        let changes: Vec<(Change, ItemType, &str)> = plan
+            .changes
+            .iter()
+            .map(|planned| (planned.change, planned.item_type, planned.id.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                (Change::Delete, ItemType::BehaviorPolicy, "policy-c"),
+                (Change::Delete, ItemType::DataPolicy, "policy-a"),
+                (Change::Delete, ItemType::DataPolicy, "policy-b"),
+                (Change::Delete, ItemType::Script, "script-1"),
+            ],
+            changes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply() {
+        let broker = MockServer::start();
+        let created_schema = schema("schema-1", "{}");
+        let updated_policy = data_policy("policy-1", "topic/#");
+        let create_schema_mock = broker.mock(|when, then| {
+            when.method(POST).path("/api/v1/data-hub/schemas");
+            then.status(201)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&created_schema).unwrap());
+        });
+        let update_policy_mock = broker.mock(|when, then| {
+            when.method(PUT)
+                .path("/api/v1/data-hub/data-validation/policies/policy-1");
+            then.status(200)
+                .header("content-type", "application/json")
+                .body(serde_json::to_string(&updated_policy).unwrap());
+        });
+        let delete_policy_mock = broker.mock(|when, then| {
+            when.method(DELETE)
+                .path("/api/v1/data-hub/behavior-validation/policies/behavior-1");
+            then.status(204);
+        });
+        let current = DataHubConfig {
+            data_policies: vec![data_policy("policy-1", "other/#")],
+            behavior_policies: vec![behavior_policy("behavior-1")],
+            ..DataHubConfig::default()
+        };
+        let desired = DataHubConfig {
+            schemas: vec![created_schema.clone()],
+            data_policies: vec![updated_policy.clone()],
+            ..DataHubConfig::default()
+        };
+        let plan = Plan::new(&current, &desired).unwrap();
+        let service = DataHubSyncService::new(&HivemqConnection::new(broker.base_url()));
+
+        let applied = service.apply(&plan).await;
+
+        assert_eq!(Ok(3), applied);
+        create_schema_mock.assert();
+        update_policy_mock.assert();
+        delete_policy_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_apply_stops_at_failure() {
+        let broker = MockServer::start();
+        broker.mock(|when, then| {
+            when.method(POST).path("/api/v1/data-hub/schemas");
+            then.status(400);
+        });
+        let desired = DataHubConfig {
+            schemas: vec![schema("schema-1", "{}"), schema("schema-2", "{}")],
+            ..DataHubConfig::default()
+        };
+        let plan = Plan::new(&DataHubConfig::default(), &desired).unwrap();
+        let service = DataHubSyncService::new(&HivemqConnection::new(broker.base_url()));
+
+        let applied = service.apply(&plan).await;
+
+        let message = applied.unwrap_err();
+        assert!(
+            message.starts_with("Applied 0 of 2 changes. Create Schema 'schema-1' failed: "),
+            "{message}"
+        );
+    }
+}
@@ -8,7 +8,7 @@ use hivemq_openapi::apis::data_hub_data_policies_api::{
 use hivemq_openapi::models::DataPolicy;
 
 use crate::hivemq_rest_client;
-use crate::hivemq_rest_client::{get_cursor, transform_api_err};
+use crate::hivemq_rest_client::{get_cursor, transform_api_err, HivemqConnection};
 use crate::repository::Repository;
 
 pub struct DataPolicyService {
@@ -17,8 +17,8 @@ pub struct DataPolicyService {
 }
 
 impl DataPolicyService {
-    pub fn new(repository: Arc<Repository<DataPolicy>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
+    pub fn new(repository: Arc<Repository<DataPolicy>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
         DataPolicyService { repository, config }
     }
 
@@ -123,6 +123,7 @@ mod tests {
     use r2d2_sqlite::SqliteConnectionManager;
     use serde_json::json;
 
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
     use crate::services::data_policy_service::DataPolicyService;
 
@@ -161,7 +162,8 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = DataPolicyService::new(repo.clone(), &broker.base_url());
+        let service =
+            DataPolicyService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
         (broker, connection_pool, repo, service)
     }
 
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use hivemq_openapi::apis::configuration::Configuration;
+use hivemq_openapi::apis::diagnostic_archive_api::create_diagnostic_archive;
+use hivemq_openapi::models::DiagnosticArchive;
+
+use crate::hivemq_rest_client;
+use crate::hivemq_rest_client::{transform_api_err, HivemqConnection};
+use crate::repository::Repository;
+
+/// The REST API can only create diagnostic archives, it can not list them.
+/// Therefore only the archives created in this session are known.
+pub struct DiagnosticArchiveService {
+    repository: Arc<Repository<DiagnosticArchive>>,
+    config: Configuration,
+}
+
+impl DiagnosticArchiveService {
+    pub fn new(
+        repository: Arc<Repository<DiagnosticArchive>>,
+        connection: &HivemqConnection,
+    ) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
+        DiagnosticArchiveService { repository, config }
+    }
+
+    pub async fn create_diagnostic_archive(&self) -> Result<String, String> {
+        let response = create_diagnostic_archive(&self.config)
+            .await
+            .map_err(transform_api_err)?;
+
+        let diagnostic_archive = response
+            .diagnostic_archive
+            .ok_or_else(|| String::from("No diagnostic archive was created"))?;
+        self.repository.save(&diagnostic_archive).unwrap();
+        diagnostic_archive
+            .id
+            .ok_or_else(|| String::from("Diagnostic archive id was empty"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use hivemq_openapi::models::diagnostic_archive::State;
+    use hivemq_openapi::models::{DiagnosticArchive, DiagnosticArchiveItem};
+    use httpmock::Method::POST;
+    use httpmock::MockServer;
+    use r2d2::Pool;
+    use r2d2_sqlite::SqliteConnectionManager;
+
+    use crate::hivemq_rest_client::HivemqConnection;
+    use crate::repository::Repository;
+    use crate::services::diagnostic_archive_service::DiagnosticArchiveService;
+
+    fn setup() -> (
+        MockServer,
+        Arc<Repository<DiagnosticArchive>>,
+        DiagnosticArchiveService,
+    ) {
+        let broker = MockServer::start();
+        let connection_pool = Pool::new(SqliteConnectionManager::memory()).unwrap();
+        let repo = Repository::<DiagnosticArchive>::init(
+            &connection_pool,
+            "diagnostic_archives",
+            |archive| archive.id.clone().unwrap(),
+            "createdAt",
+        )
+        .unwrap();
+        let repo = Arc::new(repo);
+        let service =
+            DiagnosticArchiveService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
+        (broker, repo, service)
+    }
+
+    #[tokio::test]
+    async fn test_create_diagnostic_archive() {
+        let (broker, repo, service) = setup();
+
+        let mut diagnostic_archive = DiagnosticArchive::new();
+        diagnostic_archive.id = Some("20230201-192318".to_string());
+        diagnostic_archive.created_at = Some("2023-01-02T08:13:17Z".to_string());
+        diagnostic_archive.state = Some(State::InProgress);
+        let response = DiagnosticArchiveItem {
+            diagnostic_archive: Some(Box::new(diagnostic_archive.clone())),
+        };
+        broker.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/management/diagnostic-archives");
+            then.status(200)
+                .body(serde_json::to_string(&response).unwrap());
+        });
+
+        let archive_id = service.create_diagnostic_archive().await.unwrap();
+
+        assert_eq!("20230201-192318", archive_id);
+        assert_eq!(diagnostic_archive, repo.find_by_id(&archive_id).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_create_diagnostic_archive_error() {
+        let (broker, _repo, service) = setup();
+
+        broker.mock(|when, then| {
+            when.any_request().method(POST);
+            then.status(503).body("{}");
+        });
+
+        let response = service.create_diagnostic_archive().await;
+
+        assert!(response.is_err());
+    }
+}
@@ -7,7 +7,7 @@ use hivemq_openapi::apis::data_hub_schemas_api::{
 use hivemq_openapi::models::Schema;
 
 use crate::hivemq_rest_client;
-use crate::hivemq_rest_client::{get_cursor, transform_api_err};
+use crate::hivemq_rest_client::{get_cursor, transform_api_err, HivemqConnection};
 use crate::repository::Repository;
 
 pub struct SchemaService {
@@ -16,8 +16,8 @@ pub struct SchemaService {
 }
 
 impl SchemaService {
-    pub fn new(repository: Arc<Repository<Schema>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
+    pub fn new(repository: Arc<Repository<Schema>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
         SchemaService { repository, config }
     }
 
@@ -94,6 +94,7 @@ mod tests {
     use r2d2_sqlite::SqliteConnectionManager;
     use serde_json::json;
 
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
     use crate::services::schema_service::SchemaService;
 
@@ -133,7 +134,7 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = SchemaService::new(repo.clone(), &broker.base_url());
+        let service = SchemaService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
         (broker, connection_pool, repo, service)
     }
 
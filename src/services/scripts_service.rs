@@ -7,7 +7,7 @@ use hivemq_openapi::apis::data_hub_scripts_api::{
 use hivemq_openapi::models::Script;
 
 use crate::hivemq_rest_client;
-use crate::hivemq_rest_client::{get_cursor, transform_api_err};
+use crate::hivemq_rest_client::{get_cursor, transform_api_err, HivemqConnection};
 use crate::repository::Repository;
 
 pub struct ScriptService {
@@ -16,8 +16,8 @@ pub struct ScriptService {
 }
 
 impl ScriptService {
-    pub fn new(repository: Arc<Repository<Script>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
+    pub fn new(repository: Arc<Repository<Script>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
         ScriptService { repository, config }
     }
 
@@ -95,6 +95,7 @@ mod tests {
     use r2d2_sqlite::SqliteConnectionManager;
     use serde_json::json;
 
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
     use crate::services::scripts_service::ScriptService;
 
@@ -134,7 +135,7 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = ScriptService::new(repo.clone(), &broker.base_url());
+        let service = ScriptService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
         (broker, connection_pool, repo, service)
     }
 
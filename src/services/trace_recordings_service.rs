@@ -1,14 +1,16 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use hivemq_openapi::apis::configuration::Configuration;
 use hivemq_openapi::apis::trace_recordings_api::{
-    create_trace_recording, delete_trace_recording, get_all_trace_recordings,
-    CreateTraceRecordingParams, DeleteTraceRecordingParams,
+    create_trace_recording, delete_trace_recording, get_all_trace_recordings, stop_trace_recording,
+    CreateTraceRecordingParams, DeleteTraceRecordingParams, StopTraceRecordingParams,
 };
+use hivemq_openapi::models::trace_recording::State;
 use hivemq_openapi::models::{TraceRecording, TraceRecordingItem};
 
 use crate::hivemq_rest_client;
-use crate::hivemq_rest_client::transform_api_err;
+use crate::hivemq_rest_client::{transform_api_err, HivemqConnection};
 use crate::repository::Repository;
 
 pub struct TraceRecordingService {
@@ -17,8 +19,8 @@ pub struct TraceRecordingService {
 }
 
 impl TraceRecordingService {
-    pub fn new(repository: Arc<Repository<TraceRecording>>, host: &str) -> Self {
-        let config = hivemq_rest_client::build_rest_api_config(host.to_string());
+    pub fn new(repository: Arc<Repository<TraceRecording>>, connection: &HivemqConnection) -> Self {
+        let config = hivemq_rest_client::build_rest_api_config(connection);
         TraceRecordingService { repository, config }
     }
 
@@ -34,6 +36,60 @@ impl TraceRecordingService {
         Ok(())
     }
 
+    /// Reloads the trace recordings and returns the trace recordings
+    /// that were scheduled or in progress before and are not anymore,
+    /// and the names of the running trace recordings that are not on the broker anymore.
+    /// These are removed from the repository.
+    pub async fn refresh_running_trace_recordings(
+        &self,
+    ) -> Result<(Vec<TraceRecording>, Vec<String>), String> {
+        let running_names: Vec<String> = self
+            .find_running_trace_recordings()
+            .into_iter()
+            .filter_map(|trace_recording| trace_recording.name)
+            .collect();
+
+        let response = get_all_trace_recordings(&self.config)
+            .await
+            .map_err(transform_api_err)?;
+        let trace_recordings = response.items.unwrap_or_default();
+        for trace_recording in &trace_recordings {
+            self.repository.save(trace_recording).unwrap();
+        }
+
+        let mut finished = vec![];
+        let mut removed = vec![];
+        for name in running_names {
+            match trace_recordings
+                .iter()
+                .find(|trace_recording| trace_recording.name.as_ref() == Some(&name))
+            {
+                Some(trace_recording) if !is_running(trace_recording) => {
+                    finished.push(trace_recording.clone())
+                }
+                Some(_) => {}
+                None => {
+                    self.repository.delete_by_id(&name).unwrap();
+                    removed.push(name);
+                }
+            }
+        }
+        Ok((finished, removed))
+    }
+
+    pub fn has_running_trace_recordings(&self) -> bool {
+        !self.find_running_trace_recordings().is_empty()
+    }
+
+    fn find_running_trace_recordings(&self) -> Vec<TraceRecording> {
+        self.repository
+            .find_all()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(is_running)
+            .collect()
+    }
+
     pub async fn create_trace_recording(&self, trace_recording: &String) -> Result<String, String> {
         let trace_recording: TraceRecordingItem =
             serde_json::from_str(trace_recording.as_str()).or_else(|err| Err(err.to_string()))?;
@@ -64,6 +120,52 @@ impl TraceRecordingService {
 
         Ok(trace_recording_id.to_string())
     }
+
+    /// Stops the trace recording early, so its recorded data is kept.
+    pub async fn stop_trace_recording(&self, trace_recording_id: &str) -> Result<String, String> {
+        let mut trace_recording = TraceRecording::new();
+        trace_recording.state = Some(State::Stopped);
+        let params = StopTraceRecordingParams {
+            trace_recording_id: trace_recording_id.to_owned(),
+            trace_recording_item: Some(TraceRecordingItem::new(trace_recording)),
+        };
+
+        let response = stop_trace_recording(&self.config, params)
+            .await
+            .map_err(transform_api_err)?;
+
+        self.repository.save(&response.trace_recording).unwrap();
+
+        Ok(trace_recording_id.to_string())
+    }
+
+    /// Downloads the trace recording file into the directory and returns the path of the file.
+    pub async fn download_trace_recording<F: FnMut(u64, Option<u64>)>(
+        &self,
+        trace_recording_id: &str,
+        directory: &Path,
+        on_progress: F,
+    ) -> Result<PathBuf, String> {
+        let path = format!(
+            "/api/v1/management/files/trace-recordings/{}",
+            hivemq_openapi::apis::urlencode(trace_recording_id)
+        );
+        hivemq_rest_client::download_file(
+            &self.config,
+            &path,
+            directory,
+            trace_recording_id,
+            on_progress,
+        )
+        .await
+    }
+}
+
+fn is_running(trace_recording: &TraceRecording) -> bool {
+    matches!(
+        trace_recording.state,
+        Some(State::Scheduled) | Some(State::InProgress)
+    )
 }
 
 #[cfg(test)]
@@ -72,14 +174,17 @@ mod tests {
 
     use hivemq_openapi::apis::trace_recordings_api::{
         CreateTraceRecordingError, DeleteTraceRecordingError, GetAllTraceRecordingsError,
+        StopTraceRecordingError,
     };
+    use hivemq_openapi::models::trace_recording::State;
     use hivemq_openapi::models::{Errors, TraceRecording, TraceRecordingItem, TraceRecordingList};
-    use httpmock::Method::{DELETE, GET, POST};
+    use httpmock::Method::{DELETE, GET, PATCH, POST};
     use httpmock::MockServer;
     use r2d2::Pool;
     use r2d2_sqlite::SqliteConnectionManager;
     use serde_json::{json, Value};
 
+    use crate::hivemq_rest_client::HivemqConnection;
     use crate::repository::Repository;
     use crate::services::trace_recordings_service::TraceRecordingService;
 
@@ -113,7 +218,8 @@ mod tests {
         )
         .unwrap();
         let repo = Arc::new(repo);
-        let service = TraceRecordingService::new(repo.clone(), &broker.base_url());
+        let service =
+            TraceRecordingService::new(repo.clone(), &HivemqConnection::new(broker.base_url()));
         (broker, connection_pool, repo, service)
     }
 
@@ -220,4 +326,101 @@ mod tests {
         let response = service.delete_trace_recording("trace_recording-1").await;
         assert!(response.is_err())
     }
+
+    #[tokio::test]
+    async fn test_download_trace_recording() {
+        let (broker, _pool, _repo, service) = setup();
+
+        broker.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v1/management/files/trace-recordings/trace-recording-1");
+            then.status(200).body("trace recording content");
+        });
+        let temp_dir = tempfile::tempdir().unwrap();
+        let directory = temp_dir.path().to_path_buf();
+
+        let path = service
+            .download_trace_recording("trace-recording-1", &directory, |_, _| {})
+            .await
+            .unwrap();
+
+        assert_eq!(directory.join("trace-recording-1"), path);
+        assert_eq!(
+            "trace recording content",
+            std::fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_refresh_running_trace_recordings() {
+        let (broker, _pool, repo, service) = setup();
+
+        let mut scheduled = build_trace_recording(1);
+        scheduled.state = Some(State::Scheduled);
+        let mut in_progress = build_trace_recording(2);
+        in_progress.state = Some(State::InProgress);
+        repo.save(&scheduled).unwrap();
+        repo.save(&in_progress).unwrap();
+
+        let mut stopped = scheduled.clone();
+        stopped.state = Some(State::Stopped);
+        let trace_recording_list = TraceRecordingList {
+            items: Some(vec![stopped.clone(), in_progress]),
+        };
+        broker.mock(|when, then| {
+            when.method(GET).path("/api/v1/management/trace-recordings");
+            then.status(200)
+                .body(serde_json::to_string(&trace_recording_list).unwrap());
+        });
+
+        let (result, removed) = service.refresh_running_trace_recordings().await.unwrap();
+
+        assert_eq!(vec![stopped.clone()], result);
+        assert!(removed.is_empty());
+        assert_eq!(stopped, repo.find_by_id("trace-recording-1").unwrap());
+        assert!(service.has_running_trace_recordings());
+    }
+
+    #[tokio::test]
+    async fn test_stop_trace_recording() {
+        let (broker, _pool, repo, service) = setup();
+
+        let mut trace_recording = build_trace_recording(1);
+        trace_recording.state = Some(State::InProgress);
+        repo.save(&trace_recording).unwrap();
+
+        trace_recording.state = Some(State::Stopped);
+        let response = TraceRecordingItem::new(trace_recording.clone());
+        let stop_mock = broker.mock(|when, then| {
+            when.method(PATCH)
+                .path("/api/v1/management/trace-recordings/trace-recording-1")
+                .json_body(json!({"traceRecording": {"state": "STOPPED"}}));
+            then.status(200)
+                .body(serde_json::to_string(&response).unwrap());
+        });
+
+        let result = service.stop_trace_recording("trace-recording-1").await;
+
+        stop_mock.assert();
+        assert_eq!(Ok("trace-recording-1".to_string()), result);
+        assert_eq!(
+            trace_recording,
+            repo.find_by_id("trace-recording-1").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_trace_recording_error() {
+        let (broker, _pool, _repo, service) = setup();
+
+        let error = StopTraceRecordingError::Status404(Errors::new());
+        broker.mock(|when, then| {
+            when.any_request().method(PATCH);
+            then.status(404)
+                .body(serde_json::to_string(&error).unwrap());
+        });
+
+        let response = service.stop_trace_recording("trace-recording-1").await;
+        assert!(response.is_err())
+    }
 }
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use ratatui::style::{Color, Modifier, Style};
+use serde::Deserialize;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme all components are drawn with. Only the first call has an effect.
+pub fn init_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Returns the theme set with [init_theme] or the dark theme if none was set.
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::dark)
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    #[serde(alias = "high-contrast")]
+    HighContrast,
+}
+
+/// The named styles the components are drawn with.
+/// Every style can be overridden in the `Home` section of the `styles` config.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    /// The selected tab in the header and the filter popup
+    pub tab_highlight: Style,
+    /// The key hints in the footer
+    pub key_hint: Style,
+    /// The selected item of a list
+    pub list_selection: Style,
+    /// Informational text, e.g. messages of popups and loading titles
+    pub accent: Style,
+    pub error: Style,
+    pub success: Style,
+    pub editor_cursor: Style,
+    /// The matches of the search in the details editor
+    pub search_match: Style,
+    pub json_key: Style,
+    pub json_string: Style,
+    pub json_number: Style,
+    pub json_literal: Style,
+    pub json_punctuation: Style,
+    /// The bracket under the cursor and its matching bracket
+    pub json_bracket_match: Style,
+    /// The position of a JSON error in the create and update editors
+    pub json_error: Style,
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Theme::dark(),
+            ThemeName::Light => Theme::light(),
+            ThemeName::HighContrast => Theme::high_contrast(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Theme {
+            tab_highlight: Style::default()
+                .bg(Color::Blue)
+                .remove_modifier(Modifier::DIM)
+                .add_modifier(Modifier::UNDERLINED),
+            key_hint: Style::default().fg(Color::White).bg(Color::Blue),
+            list_selection: Style::default()
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            accent: Style::default().fg(Color::Blue),
+            error: Style::default().fg(Color::Red),
+            success: Style::default().fg(Color::Green),
+            editor_cursor: Style::default().bg(Color::Blue),
+            search_match: Style::default().fg(Color::Black).bg(Color::Yellow),
+            json_key: Style::default().fg(Color::Cyan),
+            json_string: Style::default().fg(Color::Green),
+            json_number: Style::default().fg(Color::Magenta),
+            json_literal: Style::default().fg(Color::Yellow),
+            json_punctuation: Style::default(),
+            json_bracket_match: Style::default()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::UNDERLINED),
+            json_error: Style::default().fg(Color::White).bg(Color::Red),
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            tab_highlight: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .remove_modifier(Modifier::DIM)
+                .add_modifier(Modifier::UNDERLINED),
+            key_hint: Style::default().fg(Color::White).bg(Color::Blue),
+            list_selection: Style::default()
+                .fg(Color::White)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            accent: Style::default().fg(Color::Blue),
+            error: Style::default().fg(Color::Red),
+            success: Style::default().fg(Color::Green),
+            editor_cursor: Style::default().fg(Color::White).bg(Color::Blue),
+            search_match: Style::default().fg(Color::Black).bg(Color::Yellow),
+            json_key: Style::default().fg(Color::Blue),
+            json_string: Style::default().fg(Color::Green),
+            json_number: Style::default().fg(Color::Magenta),
+            json_literal: Style::default().fg(Color::Red),
+            json_punctuation: Style::default(),
+            json_bracket_match: Style::default()
+                .add_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::UNDERLINED),
+            json_error: Style::default().fg(Color::White).bg(Color::Red),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Theme {
+            tab_highlight: bold
+                .fg(Color::Black)
+                .bg(Color::Yellow)
+                .remove_modifier(Modifier::DIM)
+                .add_modifier(Modifier::UNDERLINED),
+            key_hint: bold.fg(Color::Black).bg(Color::White),
+            list_selection: bold.fg(Color::Black).bg(Color::Yellow),
+            accent: bold.fg(Color::LightCyan),
+            error: bold.fg(Color::LightRed),
+            success: bold.fg(Color::LightGreen),
+            editor_cursor: Style::default().fg(Color::Black).bg(Color::Yellow),
+            search_match: bold.fg(Color::Black).bg(Color::LightCyan),
+            json_key: bold.fg(Color::LightCyan),
+            json_string: Style::default().fg(Color::LightGreen),
+            json_number: Style::default().fg(Color::LightMagenta),
+            json_literal: Style::default().fg(Color::LightYellow),
+            json_punctuation: Style::default().fg(Color::White),
+            json_bracket_match: bold
+                .fg(Color::LightYellow)
+                .add_modifier(Modifier::UNDERLINED),
+            json_error: bold.fg(Color::Black).bg(Color::LightRed),
+        }
+    }
+
+    /// Replaces the styles of the theme with the given ones.
+    /// Returns an error naming the first style that does not exist.
+    pub fn with_styles(mut self, styles: &HashMap<String, Style>) -> Result<Self, String> {
+        for (name, style) in styles {
+            let field = match name.as_str() {
+                "tab_highlight" => &mut self.tab_highlight,
+                "key_hint" => &mut self.key_hint,
+                "list_selection" => &mut self.list_selection,
+                "accent" => &mut self.accent,
+                "error" => &mut self.error,
+                "success" => &mut self.success,
+                "editor_cursor" => &mut self.editor_cursor,
+                "search_match" => &mut self.search_match,
+                "json_key" => &mut self.json_key,
+                "json_string" => &mut self.json_string,
+                "json_number" => &mut self.json_number,
+                "json_literal" => &mut self.json_literal,
+                "json_punctuation" => &mut self.json_punctuation,
+                "json_bracket_match" => &mut self.json_bracket_match,
+                "json_error" => &mut self.json_error,
+                _ => return Err(format!("Unknown style '{name}'")),
+            };
+            *field = *style;
+        }
+        Ok(self)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ratatui::style::{Color, Style};
+
+    use crate::theme::Theme;
+
+    #[test]
+    fn test_with_styles() {
+        let styles = HashMap::from([(
+            "key_hint".to_string(),
+            Style::default().fg(Color::Black).bg(Color::White),
+        )]);
+
+        let theme = Theme::dark().with_styles(&styles).unwrap();
+
+        assert_eq!(
+            Style::default().fg(Color::Black).bg(Color::White),
+            theme.key_hint
+        );
+        assert_eq!(Theme::dark().accent, theme.accent);
+    }
+
+    #[test]
+    fn test_with_unknown_style() {
+        let styles = HashMap::from([("title".to_string(), Style::default())]);
+
+        let result = Theme::dark().with_styles(&styles);
+
+        assert_eq!(Err("Unknown style 'title'".to_string()), result);
+    }
+}
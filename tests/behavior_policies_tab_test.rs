@@ -35,7 +35,7 @@ async fn test_behavior_policies_tab() {
     )
     .unwrap();
     let repository = Arc::new(repository);
-    let service = BehaviorPolicyService::new(repository.clone(), &hivemq.host.clone());
+    let service = BehaviorPolicyService::new(repository.clone(), &hivemq.connection());
 
     for i in 0..100 {
         let behavior_policy = BehaviorPolicy::new(
@@ -52,7 +52,7 @@ async fn test_behavior_policies_tab() {
     let (tx, mut rx): (UnboundedSender<Action>, UnboundedReceiver<Action>) =
         mpsc::unbounded_channel();
     let mode = Rc::new(RefCell::new(Mode::Home));
-    let mut tab = BehaviorPoliciesTab::new(tx, hivemq.host, mode.clone(), &sqlite_pool);
+    let mut tab = BehaviorPoliciesTab::new(tx, hivemq.connection(), mode.clone(), &sqlite_pool);
     tab.activate().unwrap();
 
     tab.update(Action::LoadAllItems).unwrap();
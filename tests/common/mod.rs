@@ -1,5 +1,6 @@
 use crossterm::event::{KeyCode, KeyEvent};
 use hivemq_openapi::apis::configuration::Configuration;
+use hmq_tui::hivemq_rest_client::HivemqConnection;
 use hmq_tui::repository::Repository;
 use hmq_tui::{action::Action, components::tabs::TabComponent};
 
@@ -38,6 +39,10 @@ impl<'a> Hivemq<'a> {
         Hivemq { container, host }
     }
 
+    pub fn connection(&self) -> HivemqConnection {
+        HivemqConnection::new(self.host.clone())
+    }
+
     pub async fn enable_data_hub_trial(&self) {
         let mut config = Configuration::default();
         config.base_path = self.host.clone();
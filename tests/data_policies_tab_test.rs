@@ -37,7 +37,7 @@ async fn test_data_policies_tab() {
     )
     .unwrap();
     let repository = Arc::new(repository);
-    let service = DataPolicyService::new(repository.clone(), &hivemq.host.clone());
+    let service = DataPolicyService::new(repository.clone(), &hivemq.connection());
 
     for i in 0..100 {
         let data_policy = DataPolicy::new(
@@ -53,7 +53,7 @@ async fn test_data_policies_tab() {
     let (tx, mut rx): (UnboundedSender<Action>, UnboundedReceiver<Action>) =
         mpsc::unbounded_channel();
     let mode = Rc::new(RefCell::new(Mode::Home));
-    let mut tab = DataPoliciesTab::new(tx, hivemq.host, mode.clone(), &sqlite_pool);
+    let mut tab = DataPoliciesTab::new(tx, hivemq.connection(), mode.clone(), &sqlite_pool);
     tab.activate().unwrap();
 
     tab.update(Action::LoadAllItems).unwrap();
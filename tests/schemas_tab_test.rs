@@ -33,7 +33,7 @@ async fn test_schemas_tab() {
         Repository::<Schema>::init(&sqlite_pool, "schemas", |val| val.id.clone(), "createdAt")
             .unwrap();
     let repository = Arc::new(repository);
-    let service = SchemaService::new(repository.clone(), &hivemq.host.clone());
+    let service = SchemaService::new(repository.clone(), &hivemq.connection());
 
     for i in 0..100 {
         let schema = Schema::new(
@@ -50,7 +50,7 @@ async fn test_schemas_tab() {
     let (tx, mut rx): (UnboundedSender<Action>, UnboundedReceiver<Action>) =
         mpsc::unbounded_channel();
     let mode = Rc::new(RefCell::new(Mode::Home));
-    let mut tab = SchemasTab::new(tx, hivemq.host, mode.clone(), &sqlite_pool);
+    let mut tab = SchemasTab::new(tx, hivemq.connection(), mode.clone(), &sqlite_pool);
     tab.activate().unwrap();
 
     tab.update(Action::LoadAllItems).unwrap();